@@ -0,0 +1,143 @@
+use crate::error::EnvDeserializationError;
+
+/// How binary data is written in environment variables, see [`crate::Config::with_binary_encoding`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryEncoding {
+    /// Base64 with the standard alphabet (using `+` and `/`), e.g. `aGk/Pw==`
+    Base64,
+    /// Base64 with the URL-safe alphabet (using `-` and `_`), e.g. `aGk_Pw`
+    Base64UrlSafe,
+    /// Hexadecimal digits, two per byte, e.g. `68693f3f`
+    Hex,
+}
+
+impl BinaryEncoding {
+    /// Decodes the given value.
+    ///
+    /// Padding with `=` is optional for base64, and hexadecimal digits are case insensitive. The
+    /// returned errors never contain the value itself, as it is usually a secret.
+    pub(crate) fn decode(&self, value: &str) -> Result<Vec<u8>, EnvDeserializationError> {
+        match self {
+            Self::Base64 => decode_base64(value, b'+', b'/'),
+            Self::Base64UrlSafe => decode_base64(value, b'-', b'_'),
+            Self::Hex => decode_hex(value),
+        }
+        .map_err(EnvDeserializationError::InvalidBinary)
+    }
+}
+
+fn decode_base64(value: &str, char_62: u8, char_63: u8) -> Result<Vec<u8>, String> {
+    let data = value.trim_end_matches('=').as_bytes();
+    if value.len() - data.len() > 2
+        || (value.len() > data.len() && matches!(value.len() % 4, 1..=3))
+    {
+        return Err(String::from("invalid base64 padding"));
+    }
+    if data.len() % 4 == 1 {
+        return Err(format!(
+            "invalid base64 length of {} characters",
+            data.len()
+        ));
+    }
+
+    let mut decoded = Vec::with_capacity(data.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for (position, &byte) in data.iter().enumerate() {
+        let sextet = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            _ if byte == char_62 => 62,
+            _ if byte == char_63 => 63,
+            _ => return Err(format!("invalid base64 character at position {}", position)),
+        };
+
+        buffer = (buffer << 6) | u32::from(sextet);
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    Ok(decoded)
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>, String> {
+    let data = value.as_bytes();
+    if data.len() % 2 == 1 {
+        return Err(format!("odd hex length of {} characters", data.len()));
+    }
+
+    data.chunks(2)
+        .enumerate()
+        .map(|(index, pair)| {
+            let digit = |offset: usize| {
+                (pair[offset] as char)
+                    .to_digit(16)
+                    .ok_or_else(|| format!("invalid hex digit at position {}", index * 2 + offset))
+            };
+
+            Ok((digit(0)? << 4 | digit(1)?) as u8)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BinaryEncoding;
+    use crate::EnvDeserializationError;
+
+    #[test]
+    fn decode_base64() {
+        assert_eq!(BinaryEncoding::Base64.decode("aGk/Pw==").unwrap(), b"hi??");
+        assert_eq!(BinaryEncoding::Base64.decode("aGk/Pw").unwrap(), b"hi??");
+        assert_eq!(BinaryEncoding::Base64.decode("Zm9vYg==").unwrap(), b"foob");
+        assert_eq!(BinaryEncoding::Base64.decode("Zm9vYmE=").unwrap(), b"fooba");
+        assert_eq!(
+            BinaryEncoding::Base64.decode("Zm9vYmFy").unwrap(),
+            b"foobar"
+        );
+        assert_eq!(BinaryEncoding::Base64.decode("").unwrap(), b"");
+        assert_eq!(
+            BinaryEncoding::Base64UrlSafe.decode("aGk_Pw").unwrap(),
+            b"hi??"
+        );
+    }
+
+    #[test]
+    fn reject_invalid_base64() {
+        for value in ["aGk_Pw", "a", "aGk/P===", "aGk=Pw==", "aGk/Pw="] {
+            assert!(
+                matches!(
+                    BinaryEncoding::Base64.decode(value),
+                    Err(EnvDeserializationError::InvalidBinary(_))
+                ),
+                "{} should be rejected",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn decode_hex() {
+        assert_eq!(BinaryEncoding::Hex.decode("68693f3F").unwrap(), b"hi??");
+        assert_eq!(BinaryEncoding::Hex.decode("").unwrap(), b"");
+        assert_eq!(
+            BinaryEncoding::Hex.decode("686"),
+            Err(EnvDeserializationError::InvalidBinary(String::from(
+                "odd hex length of 3 characters"
+            )))
+        );
+        assert_eq!(
+            BinaryEncoding::Hex.decode("68xy"),
+            Err(EnvDeserializationError::InvalidBinary(String::from(
+                "invalid hex digit at position 2"
+            )))
+        );
+    }
+}
@@ -1,8 +1,48 @@
-use std::{borrow::Cow, ops::Not};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    ops::Not,
+    rc::Rc,
+    sync::Arc,
+    time::SystemTime,
+};
 
 use serde::de::DeserializeOwned;
 
-use crate::{error, error::EnvDeserializationError, value::Parser, Value};
+use crate::{
+    error,
+    error::EnvDeserializationError,
+    value::{split_inline_map, suggest, Context, Parser},
+    BinaryEncoding, KeyEncoding, Value,
+};
+
+/// Simple values nested below another value, given by their path relative to it
+type NestedValues = Vec<(Vec<String>, String)>;
+
+/// The types that values can be inferred as, see [`Config::with_type_inference`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InferredType {
+    /// `true` or `false`
+    Bool,
+    /// Integers like `42` or `-1`, as long as they fit into an `i64` or `u64`
+    Integer,
+    /// Floating point numbers like `2.5` or `1e-3`
+    Float,
+    /// Any value, as it was given
+    String,
+}
+
+impl InferredType {
+    /// The usual precedence: booleans, then integers, then floats, and everything else as
+    /// string.
+    pub const DEFAULT_PRECEDENCE: [InferredType; 4] = [
+        InferredType::Bool,
+        InferredType::Integer,
+        InferredType::Float,
+        InferredType::String,
+    ];
+}
 
 /// Used to configure the behaviour of the environment variable deserialization.
 ///
@@ -11,10 +51,54 @@ use crate::{error, error::EnvDeserializationError, value::Parser, Value};
 #[derive(Debug, Clone)]
 #[must_use]
 pub struct Config<'a> {
-    prefix: Option<Cow<'a, str>>,
+    prefixes: Vec<Cow<'a, str>>,
     case_sensitive: bool,
     separator: Cow<'a, str>,
     pub(crate) ordered_arrays: bool,
+    pub(crate) dense_arrays: bool,
+    accumulate_errors: bool,
+    pub(crate) strict: bool,
+    pub(crate) null_markers: Vec<Cow<'a, str>>,
+    inferred_types: Option<Vec<InferredType>>,
+    key_encoding: Option<KeyEncoding>,
+    list_delimiter: Option<Cow<'a, str>>,
+    list_delimiters: Vec<(Cow<'a, str>, Cow<'a, str>)>,
+    pub(crate) trim_list_elements: bool,
+    inline_maps: Option<(Cow<'a, str>, Cow<'a, str>)>,
+    #[cfg(feature = "json")]
+    pub(crate) json_values: bool,
+    unit_suffixes: bool,
+    unit_suffix_paths: Vec<Cow<'a, str>>,
+    true_spellings: Option<Vec<Cow<'a, str>>>,
+    false_spellings: Option<Vec<Cow<'a, str>>>,
+    strict_bools: bool,
+    pub(crate) numeric_literals: bool,
+    pub(crate) binary_encoding: Option<BinaryEncoding>,
+    pub(crate) schema_guided: bool,
+    aliases: Vec<(Cow<'a, str>, Cow<'a, str>)>,
+    deprecated_keys: Vec<DeprecatedKey<'a>>,
+    on_deprecated: Option<DeprecationCallback<'a>>,
+}
+
+/// A key that is read as another one, see [`Config::with_deprecated_key`]
+#[derive(Debug, Clone)]
+struct DeprecatedKey<'a> {
+    key: Cow<'a, str>,
+    replacement: Cow<'a, str>,
+    cutoff: Option<SystemTime>,
+}
+
+/// The function called when a deprecated key is used, see [`Config::on_deprecated`]
+type DeprecationFn<'a> = dyn Fn(&str, &str) + Send + Sync + 'a;
+
+/// Keeps [`Config`] cloneable and debuggable with a [`DeprecationFn`]
+#[derive(Clone)]
+struct DeprecationCallback<'a>(Arc<DeprecationFn<'a>>);
+
+impl fmt::Debug for DeprecationCallback<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DeprecationCallback(..)")
+    }
 }
 
 impl Default for Config<'static> {
@@ -25,17 +109,61 @@ impl Default for Config<'static> {
 }
 
 impl<'a> Config<'a> {
+    /// The spellings of `true` accepted when none are configured, see
+    /// [`Self::with_bool_spellings`]
+    pub const DEFAULT_TRUE_SPELLINGS: [&'static str; 4] = ["true", "yes", "on", "1"];
+
+    /// The spellings of `false` accepted when none are configured, see
+    /// [`Self::with_bool_spellings`]
+    pub const DEFAULT_FALSE_SPELLINGS: [&'static str; 4] = ["false", "no", "off", "0"];
+
     /// Create a new instance of [`Config`] with the following configuration:
     /// - No prefix
     /// - Case insensitive
     /// - A separator of "__" (double underscore)
-    /// - Sorted arrays
+    /// - Sorted arrays, with any indices
+    /// - Stopping at the first error
+    /// - Ignoring unknown variables
+    /// - No null markers
+    /// - No type inference
+    /// - No key encoding
+    /// - No inline lists or maps
+    /// - No JSON values (with the `json` feature)
+    /// - No unit suffixes
+    /// - Booleans spelled as in [`Self::DEFAULT_TRUE_SPELLINGS`] and
+    ///   [`Self::DEFAULT_FALSE_SPELLINGS`]
+    /// - Numbers in the format of [`str::parse`]
+    /// - No aliases or deprecated keys
+    /// - Binary data as the UTF-8 bytes of the value
     pub const fn new() -> Self {
         Self {
-            prefix: None,
+            prefixes: Vec::new(),
             case_sensitive: false,
             separator: Cow::Borrowed("__"),
             ordered_arrays: true,
+            dense_arrays: false,
+            accumulate_errors: false,
+            strict: false,
+            null_markers: Vec::new(),
+            inferred_types: None,
+            key_encoding: None,
+            list_delimiter: None,
+            list_delimiters: Vec::new(),
+            trim_list_elements: false,
+            inline_maps: None,
+            #[cfg(feature = "json")]
+            json_values: false,
+            unit_suffixes: false,
+            unit_suffix_paths: Vec::new(),
+            true_spellings: None,
+            false_spellings: None,
+            strict_bools: false,
+            numeric_literals: false,
+            binary_encoding: None,
+            schema_guided: false,
+            aliases: Vec::new(),
+            deprecated_keys: Vec::new(),
+            on_deprecated: None,
         }
     }
 
@@ -64,24 +192,275 @@ impl<'a> Config<'a> {
         self
     }
 
+    /// Configures whether variable names are split by the names of the fields they are read into.
+    ///
+    /// Defaults to `false`. If `true`, then the names of variables are only split at a separator
+    /// that follows the name of a field (or variant), so that fields can contain the separator.
+    /// This allows for the conventional names like `APP_DATABASE_MAX_CONNECTIONS` with
+    /// [`Self::with_separator`] set to `_`. Where several fields match, the longest one is used,
+    /// i.e. given the fields `max` and `max_connections`, the latter is used. A likely typo of a
+    /// field, like `APP_DATABASE_MAX_CONECTIONS`, is not split after a shorter field like `max`
+    /// either, so that it can be reported as unknown (see [`Self::strict`]).
+    ///
+    /// Names are split at the first separator for maps and sequences, as well as for
+    /// `#[serde(flatten)]`ed fields and untagged enums, as the names of their fields are not
+    /// known.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use serde::Deserialize;
+    /// #[derive(Deserialize)]
+    /// struct Database {
+    ///     max_connections: u32,
+    ///     connect_timeout_secs: u64,
+    /// }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Config {
+    ///     database: Database,
+    ///     log_level: String,
+    /// }
+    ///
+    /// let config: Config = envious::Config::new()
+    ///     .with_prefix("APP_")
+    ///     .with_separator("_")
+    ///     .schema_guided(true)
+    ///     .build_from_iter([
+    ///         ("APP_DATABASE_MAX_CONNECTIONS", "16"),
+    ///         ("APP_DATABASE_CONNECT_TIMEOUT_SECS", "5"),
+    ///         ("APP_LOG_LEVEL", "debug"),
+    ///     ])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.database.max_connections, 16);
+    /// assert_eq!(config.database.connect_timeout_secs, 5);
+    /// assert_eq!(config.log_level, "debug");
+    /// ```
+    pub fn schema_guided(&mut self, schema_guided: bool) -> &mut Self {
+        self.schema_guided = schema_guided;
+        self
+    }
+
     /// Configures the prefix to strip from environment variables names.
     ///
-    /// Environments variables without the prefix are discarded.
+    /// Environments variables without the prefix are discarded. This replaces any prefixes set
+    /// before, including those added with [`Self::with_fallback_prefix`].
     ///
     /// Defaults to no prefix being set. You can switch back to the default via [`Self::without_prefix`].
     pub fn with_prefix<S>(&mut self, prefix: S) -> &mut Self
     where
         S: Into<Cow<'a, str>>,
     {
-        self.prefix = Some(prefix.into());
+        self.prefixes = vec![prefix.into()];
+        self
+    }
+
+    /// Configures another prefix to read environment variables with, which has a lower precedence
+    /// than the prefixes added before.
+    ///
+    /// The variables of all prefixes are merged. A variable overrides the variables of prefixes
+    /// with a lower precedence that have the same name after the prefix, as well as those that are
+    /// nested below it or above it. E.g. with the prefix `BILLING_` and the fallback prefix
+    /// `COMMON_`, `BILLING_DATABASE__URL` overrides `COMMON_DATABASE__URL` and `COMMON_DATABASE`,
+    /// but not `COMMON_DATABASE_POOL`. A variable starting with several prefixes is read with the
+    /// one with the highest precedence.
+    ///
+    /// Errors name the variable with the prefix it was read with. Variables that are missing are
+    /// named with the prefix with the highest precedence.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use serde::Deserialize;
+    /// #[derive(Deserialize)]
+    /// struct Database {
+    ///     url: String,
+    ///     pool_size: u32,
+    /// }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Config {
+    ///     database: Database,
+    /// }
+    ///
+    /// let config: Config = envious::Config::new()
+    ///     .with_prefix("BILLING_")
+    ///     .with_fallback_prefix("COMMON_")
+    ///     .build_from_iter([
+    ///         ("COMMON_DATABASE__URL", "postgres://db/common"),
+    ///         ("COMMON_DATABASE__POOL_SIZE", "8"),
+    ///         ("BILLING_DATABASE__URL", "postgres://db/billing"),
+    ///     ])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.database.url, "postgres://db/billing");
+    /// assert_eq!(config.database.pool_size, 8);
+    /// ```
+    pub fn with_fallback_prefix<S>(&mut self, prefix: S) -> &mut Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.prefixes.push(prefix.into());
         self
     }
 
     /// Resets the [`Config`] to not look for a specific prefix in environment variables names.
     ///
-    /// Used to remove the effect of [`Self::with_prefix`].
+    /// Used to remove the effect of [`Self::with_prefix`] and [`Self::with_fallback_prefix`].
     pub fn without_prefix(&mut self) -> &mut Self {
-        self.prefix = None;
+        self.prefixes.clear();
+        self
+    }
+
+    /// Configures an environment variable to be read as if it was given with the given key.
+    ///
+    /// The name is the full name of the variable, which neither needs to start with the prefix
+    /// nor to be nested. This allows to read variables that are set by the platform, like `PORT`
+    /// or `DATABASE_URL`. The key is given like the name of a variable without the prefix, e.g.
+    /// `server__port` with the default separator. The name is matched case insensitively unless
+    /// [`Self::case_sensitive`] is enabled.
+    ///
+    /// If the key (or a key nested below or above it) is also given by its own variable, then that
+    /// variable takes precedence over the alias, so that the platform's default can be overridden.
+    /// If several aliases for the same key are set, the one that was added first is used.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use serde::Deserialize;
+    /// #[derive(Deserialize)]
+    /// struct Server {
+    ///     port: u16,
+    ///     host: String,
+    /// }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Config {
+    ///     server: Server,
+    ///     database_url: String,
+    /// }
+    ///
+    /// let config: Config = envious::Config::new()
+    ///     .with_prefix("APP_")
+    ///     .with_alias("PORT", "server__port")
+    ///     .with_alias("DATABASE_URL", "database_url")
+    ///     .build_from_iter([
+    ///         ("PORT", "8080"),
+    ///         ("APP_SERVER__HOST", "0.0.0.0"),
+    ///         ("DATABASE_URL", "postgres://db/app"),
+    ///         ("APP_DATABASE_URL", "postgres://localhost/app"),
+    ///     ])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.server.port, 8080);
+    /// assert_eq!(config.database_url, "postgres://localhost/app");
+    /// ```
+    pub fn with_alias<N, K>(&mut self, name: N, key: K) -> &mut Self
+    where
+        N: Into<Cow<'a, str>>,
+        K: Into<Cow<'a, str>>,
+    {
+        self.aliases.push((name.into(), key.into()));
+        self
+    }
+
+    /// Removes all aliases added with [`Self::with_alias`].
+    pub fn without_aliases(&mut self) -> &mut Self {
+        self.aliases.clear();
+        self
+    }
+
+    /// Configures a key that is no longer used to be read as the given key instead.
+    ///
+    /// Both keys are given like the name of a variable without the prefix, e.g. `db_url` and
+    /// `database__url`, so that `APP_DB_URL` is read as `APP_DATABASE__URL` with the prefix
+    /// `APP_`. Keys nested below a deprecated key are replaced as well, e.g. `db` and `database`
+    /// also read `APP_DB__URL` as `APP_DATABASE__URL`. Every time a deprecated key is used, a
+    /// warning is given to the callback set with [`Self::on_deprecated`], or logged with the `log`
+    /// and `tracing` crates if the respective feature is enabled.
+    ///
+    /// Deprecated keys are replaced separately for each prefix (see
+    /// [`Self::with_fallback_prefix`]). If the replacement (or a key nested below or above it) is
+    /// also given by its own variable with the same prefix, then that takes precedence over the
+    /// deprecated key. Otherwise the precedence of the prefixes decides, so e.g. `BILLING_DB_URL`
+    /// overrides `COMMON_DATABASE__URL` with the fallback prefix `COMMON_`. A deprecated key in
+    /// turn takes precedence over an alias (see [`Self::with_alias`]), as it is still given with
+    /// a prefix.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use serde::Deserialize;
+    /// #[derive(Deserialize)]
+    /// struct Database {
+    ///     url: String,
+    /// }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Config {
+    ///     database: Database,
+    /// }
+    ///
+    /// let config: Config = envious::Config::new()
+    ///     .with_prefix("APP_")
+    ///     .with_deprecated_key("db_url", "database__url")
+    ///     .on_deprecated(|variable, replacement| {
+    ///         eprintln!("`{}` is deprecated, use `{}` instead", variable, replacement)
+    ///     })
+    ///     .build_from_iter([("APP_DB_URL", "postgres://db/app")])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.database.url, "postgres://db/app");
+    /// ```
+    pub fn with_deprecated_key<K, R>(&mut self, key: K, replacement: R) -> &mut Self
+    where
+        K: Into<Cow<'a, str>>,
+        R: Into<Cow<'a, str>>,
+    {
+        self.deprecated_keys.push(DeprecatedKey {
+            key: key.into(),
+            replacement: replacement.into(),
+            cutoff: None,
+        });
+        self
+    }
+
+    /// Configures a key that is no longer used to be read as the given key instead, until the
+    /// given point in time.
+    ///
+    /// Works like [`Self::with_deprecated_key`] before the cutoff. Afterwards, using the
+    /// deprecated key is an error ([`EnvDeserializationError::DeprecatedVariable`]), which names
+    /// the variable to use instead.
+    pub fn with_deprecated_key_until<K, R>(
+        &mut self,
+        key: K,
+        replacement: R,
+        cutoff: SystemTime,
+    ) -> &mut Self
+    where
+        K: Into<Cow<'a, str>>,
+        R: Into<Cow<'a, str>>,
+    {
+        self.deprecated_keys.push(DeprecatedKey {
+            key: key.into(),
+            replacement: replacement.into(),
+            cutoff: Some(cutoff),
+        });
+        self
+    }
+
+    /// Configures the function to call when a deprecated key is used, see
+    /// [`Self::with_deprecated_key`].
+    ///
+    /// It gets the full names of the variable that was given and of the variable to use instead.
+    /// Defaults to logging a warning with the `log` and `tracing` crates if the respective feature
+    /// is enabled, and to nothing otherwise.
+    pub fn on_deprecated<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: Fn(&str, &str) + Send + Sync + 'a,
+    {
+        self.on_deprecated = Some(DeprecationCallback(Arc::new(callback)));
         self
     }
 
@@ -89,7 +468,14 @@ impl<'a> Config<'a> {
     ///
     /// Defaults to case insensitive.
     ///
-    /// NB: Only `struct` fields and `enum` variants, as well as any prefix provided via [`Self::with_prefix`] are affected by case sensitivity.
+    /// NB: Only the following are affected by case sensitivity:
+    /// - `struct` fields and `enum` variants
+    /// - the prefixes given with [`Self::with_prefix`] and [`Self::with_fallback_prefix`]
+    /// - the names of aliases, see [`Self::with_alias`]
+    /// - deprecated keys, see [`Self::with_deprecated_key`]
+    /// - the spellings of booleans, see [`Self::with_bool_spellings`]
+    /// - the paths given to [`Self::with_list_delimiter_for`] and
+    ///   [`Self::with_unit_suffixes_for`]
     pub fn case_sensitive(&mut self, case_sensitive: bool) -> &mut Self {
         self.case_sensitive = case_sensitive;
         self
@@ -116,6 +502,646 @@ impl<'a> Config<'a> {
         self
     }
 
+    /// Configures whether array indices have to be exactly `0` to `n - 1` for an array of `n` elements.
+    ///
+    /// Defaults to `false`. If `true`, then only non-negative integers are accepted as indices
+    /// and elements are always placed at their index. Indices that are missing in between (e.g.
+    /// only `servers__0` and `servers__2` are given) or given more than once (e.g. `servers__1`
+    /// and `servers__01`) are an error, and so are indices beyond the length of a fixed size
+    /// array (`[T; N]`) or tuple. This takes precedence over [`Self::ordered_arrays`].
+    pub fn dense_arrays(&mut self, dense_arrays: bool) -> &mut Self {
+        self.dense_arrays = dense_arrays;
+        self
+    }
+
+    /// Configures whether to collect all errors instead of stopping at the first one.
+    ///
+    /// Defaults to `false`. If `true`, then a failing value does not stop the deserialization of
+    /// the values next to it, and all errors found (e.g. values that could not be parsed,
+    /// missing fields and invalid nesting) are returned together as
+    /// [`EnvDeserializationError::Multiple`].
+    ///
+    /// NB: Errors of values containing another value that failed are not reported again. In this
+    /// mode deserialization may run several times, as it is restarted after each error.
+    pub fn accumulate_errors(&mut self, accumulate_errors: bool) -> &mut Self {
+        self.accumulate_errors = accumulate_errors;
+        self
+    }
+
+    /// Configures whether variables that do not correspond to any field are an error.
+    ///
+    /// Defaults to `false`. If `true`, then any variable that is not used by a `struct` (e.g. due
+    /// to a typo like `APP_DATABSE__URL`) is reported as
+    /// [`EnvDeserializationError::UnknownVariable`], with the closest field name as suggestion.
+    /// The same goes for additional variants given for an `enum`.
+    ///
+    /// Unknown variables are reported even if deserialization fails, e.g. because the typo also
+    /// leaves a required variable missing.
+    ///
+    /// NB: This is best combined with [`Self::with_prefix`], as otherwise every other variable in
+    /// the environment is unknown. Maps accept any key, but the fields of the structs they contain
+    /// are checked. serde buffers `#[serde(flatten)]`ed fields and internally tagged or untagged
+    /// enums as a whole, so variables below them cannot be checked. Use
+    /// `#[serde(deny_unknown_fields)]` on the struct containing a flattened field instead.
+    pub fn strict(&mut self, strict: bool) -> &mut Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Configures the values that are treated as `null`, e.g. the empty string, `null` or `none`.
+    ///
+    /// Defaults to no values. Variables set to one of these values deserialize as `None` for
+    /// `Option` fields, and as `()` for unit types and unit structs. This allows overriding a
+    /// default of `Some` with `None` from the environment.
+    ///
+    /// NB: The values are compared case sensitively.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use serde::Deserialize;
+    /// #[derive(Deserialize)]
+    /// struct Config {
+    ///     timeout: Option<u32>,
+    /// }
+    ///
+    /// let config: Config = envious::Config::new()
+    ///     .with_null_markers(["", "null"])
+    ///     .build_from_iter([("timeout", "null")])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.timeout, None);
+    /// ```
+    pub fn with_null_markers<I, S>(&mut self, null_markers: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<Cow<'a, str>>,
+    {
+        self.null_markers = null_markers.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Configures values to be given as the type they look like to types that accept any type.
+    ///
+    /// Defaults to no inference, i.e. values are always given as strings. Types like
+    /// `#[serde(untagged)]` enums accept whatever type they are given, and would therefore never
+    /// see e.g. a number. With inference, each value is tried as the given types in order, and
+    /// given as the first one it can be parsed as. Values matching none of them are given as
+    /// strings, as are all values once [`InferredType::String`] is reached. Values set to one of
+    /// the [null markers](Self::with_null_markers) are given as unit.
+    ///
+    /// This also applies to values that serde buffers before knowing their types, i.e. those of
+    /// internally tagged enums and `#[serde(flatten)]`ed fields, which therefore need inference to
+    /// contain numbers or booleans. In turn, a `String` field of those is given e.g. `1234` as a
+    /// number with inference, and fails to deserialize.
+    ///
+    /// NB: This does not affect other types that ask for a specific type, e.g. a `String` field
+    /// always accepts `1234`.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use serde::Deserialize;
+    /// # use envious::InferredType;
+    /// #[derive(Deserialize, Debug, PartialEq)]
+    /// #[serde(untagged)]
+    /// enum Port {
+    ///     Number(u16),
+    ///     Named(String),
+    /// }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Config {
+    ///     port: Port,
+    /// }
+    ///
+    /// let config: Config = envious::Config::new()
+    ///     .with_type_inference(InferredType::DEFAULT_PRECEDENCE)
+    ///     .build_from_iter([("port", "8080")])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.port, Port::Number(8080));
+    /// ```
+    pub fn with_type_inference<I>(&mut self, precedence: I) -> &mut Self
+    where
+        I: IntoIterator<Item = InferredType>,
+    {
+        self.inferred_types = Some(precedence.into_iter().collect());
+        self
+    }
+
+    /// Configures values to always be given as strings to types that accept any type.
+    ///
+    /// Used to remove the effect of [`Self::with_type_inference`].
+    pub fn without_type_inference(&mut self) -> &mut Self {
+        self.inferred_types = None;
+        self
+    }
+
+    /// Configures how characters that cannot be part of environment variable names are written.
+    ///
+    /// Defaults to no encoding. Keys of maps often contain characters like `.`, `/` or `-`, e.g.
+    /// host names, which are not allowed in environment variable names by most shells. With an
+    /// encoding, each part of a variable name between separators is decoded before being used, so
+    /// that e.g. `upstreams__api_x2Eexample_x2Ecom__port` sets the port of `api.example.com`
+    /// with [`KeyEncoding::Hex`]. See [`KeyEncoding::encode`] for getting the name to set, which
+    /// never contains the default separator `__`. Errors name the variables with encoded keys.
+    ///
+    /// NB: Decoding applies to all parts of a name, including the names of fields and variants.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use std::collections::HashMap;
+    /// # use serde::Deserialize;
+    /// # use envious::KeyEncoding;
+    /// #[derive(Deserialize)]
+    /// struct Upstream {
+    ///     port: u16,
+    /// }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Config {
+    ///     upstreams: HashMap<String, Upstream>,
+    /// }
+    ///
+    /// let config: Config = envious::Config::new()
+    ///     .with_key_encoding(KeyEncoding::Hex)
+    ///     .build_from_iter([("UPSTREAMS__API_X2EEXAMPLE_X2ECOM__PORT", "443")])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.upstreams["api.example.com"].port, 443);
+    /// ```
+    pub fn with_key_encoding(&mut self, key_encoding: KeyEncoding) -> &mut Self {
+        self.key_encoding = Some(key_encoding);
+        self
+    }
+
+    /// Configures the parts of environment variable names to be used as they are.
+    ///
+    /// Used to remove the effect of [`Self::with_key_encoding`].
+    pub fn without_key_encoding(&mut self) -> &mut Self {
+        self.key_encoding = None;
+        self
+    }
+
+    /// Configures sequences to also be read from a single variable, with elements separated by the
+    /// given delimiter.
+    ///
+    /// Defaults to no delimiter, i.e. a single variable is read as a sequence of one element.
+    /// With a delimiter, e.g. `allowed_hosts=a,b,c` is read as three elements, in addition to
+    /// giving each element in its own variable like `allowed_hosts__0=a`. The elements can be of
+    /// any type that is read from a single variable, e.g. numbers or unit enum variants. The same
+    /// goes for tuples and fixed size arrays. An empty variable is read as an empty sequence.
+    ///
+    /// A delimiter that is part of an element can be escaped with `\`, as can `\` itself.
+    ///
+    /// For only splitting specific fields, see [`Self::with_list_delimiter_for`].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use serde::Deserialize;
+    /// #[derive(Deserialize)]
+    /// struct Config {
+    ///     allowed_hosts: Vec<String>,
+    ///     ports: Vec<u16>,
+    /// }
+    ///
+    /// let config: Config = envious::Config::new()
+    ///     .with_list_delimiter(",")
+    ///     .build_from_iter([("allowed_hosts", "a,b\\,c"), ("ports", "80,443")])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.allowed_hosts, ["a", "b,c"]);
+    /// assert_eq!(config.ports, [80, 443]);
+    /// ```
+    pub fn with_list_delimiter<S>(&mut self, delimiter: S) -> &mut Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.list_delimiter = Some(delimiter.into());
+        self
+    }
+
+    /// Configures the sequence at the given path to also be read from a single variable, with
+    /// elements separated by the given delimiter.
+    ///
+    /// The path consists of the names of the fields, keys and variants leading to the sequence,
+    /// joined with `.`, e.g. `server.allowed_hosts`. This takes precedence over the delimiter
+    /// given via [`Self::with_list_delimiter`], see there for details.
+    pub fn with_list_delimiter_for<P, S>(&mut self, path: P, delimiter: S) -> &mut Self
+    where
+        P: Into<Cow<'a, str>>,
+        S: Into<Cow<'a, str>>,
+    {
+        self.list_delimiters.push((path.into(), delimiter.into()));
+        self
+    }
+
+    /// Configures sequences to only be read from one variable per element.
+    ///
+    /// Used to remove the effect of [`Self::with_list_delimiter`] and
+    /// [`Self::with_list_delimiter_for`].
+    pub fn without_list_delimiter(&mut self) -> &mut Self {
+        self.list_delimiter = None;
+        self.list_delimiters.clear();
+        self
+    }
+
+    /// Configures whether whitespace around the elements of sequences and maps read from a single
+    /// variable is removed.
+    ///
+    /// Defaults to `false`. If `true`, then e.g. `a, b , c` is read as `a`, `b` and `c`. See
+    /// [`Self::with_list_delimiter`] and [`Self::with_inline_maps`].
+    pub fn trim_list_elements(&mut self, trim_list_elements: bool) -> &mut Self {
+        self.trim_list_elements = trim_list_elements;
+        self
+    }
+
+    /// Configures maps to also be read from a single variable, with entries separated by
+    /// `pair_separator` and keys separated from values by `key_value_separator`.
+    ///
+    /// Defaults to no inline maps. With e.g. `,` and `=` as separators, `labels=team=core,tier=1`
+    /// is read as a map with the keys `team` and `tier`, in addition to giving each entry in its
+    /// own variable like `labels__team=core`. Both forms can be used for the same map, in which
+    /// case entries given in their own variable take precedence. The single variable then needs to
+    /// contain at least one key and value, as e.g. `labels=true` next to `labels__team=core` is
+    /// invalid nesting. An empty variable is read as an empty map.
+    ///
+    /// Separators that are part of a key or value can be escaped with `\`, as can `\` itself.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use std::collections::HashMap;
+    /// # use serde::Deserialize;
+    /// #[derive(Deserialize)]
+    /// struct Config {
+    ///     labels: HashMap<String, String>,
+    /// }
+    ///
+    /// let config: Config = envious::Config::new()
+    ///     .with_inline_maps(",", "=")
+    ///     .build_from_iter([("labels", "team=core,tier=1"), ("labels__tier", "2")])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.labels["team"], "core");
+    /// assert_eq!(config.labels["tier"], "2");
+    /// ```
+    pub fn with_inline_maps<P, S>(&mut self, pair_separator: P, key_value_separator: S) -> &mut Self
+    where
+        P: Into<Cow<'a, str>>,
+        S: Into<Cow<'a, str>>,
+    {
+        self.inline_maps = Some((pair_separator.into(), key_value_separator.into()));
+        self
+    }
+
+    /// Configures maps to only be read from one variable per entry.
+    ///
+    /// Used to remove the effect of [`Self::with_inline_maps`].
+    pub fn without_inline_maps(&mut self) -> &mut Self {
+        self.inline_maps = None;
+        self
+    }
+
+    /// Configures whether values can be given as JSON.
+    ///
+    /// Defaults to `false`. If `true`, then values that are read as a struct, map, sequence or
+    /// enum can also be given as a JSON object or array in a single variable, e.g.
+    /// `routes=[{"path":"/","to":"svc"}]`. Parts of it can be overridden by variables nested below
+    /// it, e.g. `routes__0__to=other` replaces the `to` of the first route. `null` is read as the
+    /// first [null marker](Self::with_null_markers), and left out if there are none.
+    ///
+    /// NB: This requires the `json` feature. Values that start with `{` or `[` are always read as
+    /// JSON when reading any of the types mentioned above, so they fail to deserialize if they are
+    /// not valid JSON.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use serde::Deserialize;
+    /// #[derive(Deserialize)]
+    /// struct Route {
+    ///     path: String,
+    ///     to: String,
+    /// }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Config {
+    ///     routes: Vec<Route>,
+    /// }
+    ///
+    /// let config: Config = envious::Config::new()
+    ///     .json_values(true)
+    ///     .build_from_iter([
+    ///         ("routes", r#"[{"path":"/","to":"svc"},{"path":"/api","to":"api"}]"#),
+    ///         ("routes__0__to", "other"),
+    ///     ])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.routes[0].to, "other");
+    /// assert_eq!(config.routes[1].to, "api");
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn json_values(&mut self, json_values: bool) -> &mut Self {
+        self.json_values = json_values;
+        self
+    }
+
+    /// Parses the given value as JSON, if it looks like a JSON object or array and JSON values are
+    /// enabled, see `Self::json_values`
+    pub(crate) fn json_value(&self, value: &str) -> Option<Result<Value, EnvDeserializationError>> {
+        #[cfg(feature = "json")]
+        if self.json_values {
+            return crate::json::parse(self, value);
+        }
+
+        let _ = value;
+        None
+    }
+
+    /// The separators of maps read from a single variable, see [`Self::with_inline_maps`]
+    fn inline_map_separators(&self) -> Option<(&str, &str)> {
+        self.inline_maps
+            .as_ref()
+            .map(|(pair, key_value)| (pair.as_ref(), key_value.as_ref()))
+            .filter(|(pair, key_value)| pair.is_empty().not() && key_value.is_empty().not())
+    }
+
+    /// Splits the given value into the keys and values of a map, if inline maps are configured,
+    /// see [`Self::with_inline_maps`]
+    pub(crate) fn inline_map_entries(
+        &self,
+        value: &str,
+    ) -> Option<Result<Vec<(String, String)>, EnvDeserializationError>> {
+        let (pair_separator, key_value_separator) = self.inline_map_separators()?;
+
+        Some(split_inline_map(
+            value,
+            pair_separator,
+            key_value_separator,
+            self.trim_list_elements,
+        ))
+    }
+
+    /// Configures whether integers can be given with a unit suffix, e.g. `512MiB` or `10k`.
+    ///
+    /// Defaults to `false`. If `true`, then integers can be followed by one of the following units,
+    /// ignoring case:
+    /// - Decimal multipliers and byte sizes: `k`/`kB` (1000), `M`/`MB`, `G`/`GB`, `T`/`TB`,
+    ///   `P`/`PB` and `E`/`EB`
+    /// - Binary byte sizes: `Ki`/`KiB` (1024), `Mi`/`MiB`, `Gi`/`GiB`, `Ti`/`TiB`, `Pi`/`PiB` and
+    ///   `Ei`/`EiB`
+    /// - Bytes: `B`
+    ///
+    /// Decimal places are allowed as long as the result is a whole number, e.g. `1.5KiB`. Results
+    /// that do not fit into the integer type are an error.
+    ///
+    /// For only allowing unit suffixes for specific fields, see [`Self::with_unit_suffixes_for`].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use serde::Deserialize;
+    /// #[derive(Deserialize)]
+    /// struct Config {
+    ///     cache_size: u64,
+    ///     rate_limit: u32,
+    /// }
+    ///
+    /// let config: Config = envious::Config::new()
+    ///     .unit_suffixes(true)
+    ///     .build_from_iter([("cache_size", "512MiB"), ("rate_limit", "10k")])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.cache_size, 512 * 1024 * 1024);
+    /// assert_eq!(config.rate_limit, 10_000);
+    /// ```
+    pub fn unit_suffixes(&mut self, unit_suffixes: bool) -> &mut Self {
+        self.unit_suffixes = unit_suffixes;
+        self
+    }
+
+    /// Configures the integer at the given path to allow a unit suffix, e.g. `512MiB` or `10k`.
+    ///
+    /// The path consists of the names of the fields, keys and variants leading to the integer,
+    /// joined with `.`, e.g. `cache.size`. See [`Self::unit_suffixes`] for details.
+    pub fn with_unit_suffixes_for<P>(&mut self, path: P) -> &mut Self
+    where
+        P: Into<Cow<'a, str>>,
+    {
+        self.unit_suffix_paths.push(path.into());
+        self
+    }
+
+    /// Whether the integer at the given path can be given with a unit suffix, see
+    /// [`Self::unit_suffixes`]
+    pub(crate) fn allows_unit_suffixes(&self, path: &[String]) -> bool {
+        self.unit_suffixes
+            || self
+                .unit_suffix_paths
+                .iter()
+                .any(|pattern| self.matches_path(pattern, path))
+    }
+
+    /// Configures whether numbers can be given in the formats of Rust literals.
+    ///
+    /// Defaults to `false`. If `true`, then integers can be given in hexadecimal (`0xFF`), octal
+    /// (`0o755`) or binary (`0b1010`), and numbers can contain `_` to separate digits (e.g.
+    /// `1_000_000`) as well as whitespace around them. A leading `+` is accepted in any case.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use serde::Deserialize;
+    /// #[derive(Deserialize)]
+    /// struct Config {
+    ///     permissions: u32,
+    ///     id: u128,
+    /// }
+    ///
+    /// let config: Config = envious::Config::new()
+    ///     .numeric_literals(true)
+    ///     .build_from_iter([("permissions", "0o755"), ("id", "0xFFFF_FFFF_FFFF_FFFF_FFFF")])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.permissions, 0o755);
+    /// assert_eq!(config.id, 0xFFFF_FFFF_FFFF_FFFF_FFFF);
+    /// ```
+    pub fn numeric_literals(&mut self, numeric_literals: bool) -> &mut Self {
+        self.numeric_literals = numeric_literals;
+        self
+    }
+
+    /// Configures how binary data is written in environment variables.
+    ///
+    /// Defaults to no encoding, in which case the UTF-8 bytes of a value are used as they are.
+    /// This applies to types deserializing from bytes, like `serde_bytes::ByteBuf` or `Vec<u8>`
+    /// fields annotated with `#[serde(with = "serde_bytes")]`. A plain `Vec<u8>` is a sequence of
+    /// integers instead, see [`Self::with_list_delimiter`].
+    ///
+    /// Errors when decoding name the variable, but never contain its value, as binary data in
+    /// configuration is usually a key or a secret.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use serde::Deserialize;
+    /// # use envious::BinaryEncoding;
+    /// #[derive(Deserialize)]
+    /// struct Config {
+    ///     #[serde(with = "serde_bytes")]
+    ///     hmac_secret: Vec<u8>,
+    /// }
+    ///
+    /// let config: Config = envious::Config::new()
+    ///     .with_binary_encoding(BinaryEncoding::Hex)
+    ///     .build_from_iter([("HMAC_SECRET", "00ff10")])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(config.hmac_secret, [0x00, 0xff, 0x10]);
+    /// ```
+    pub fn with_binary_encoding(&mut self, binary_encoding: BinaryEncoding) -> &mut Self {
+        self.binary_encoding = Some(binary_encoding);
+        self
+    }
+
+    /// Configures binary data to be read as the UTF-8 bytes of a value.
+    ///
+    /// Used to remove the effect of [`Self::with_binary_encoding`].
+    pub fn without_binary_encoding(&mut self) -> &mut Self {
+        self.binary_encoding = None;
+        self
+    }
+
+    /// Configures the spellings accepted for `true` and `false`.
+    ///
+    /// Defaults to [`Self::DEFAULT_TRUE_SPELLINGS`] and [`Self::DEFAULT_FALSE_SPELLINGS`], e.g.
+    /// `yes`, `on` and `1` for `true`. The spellings are compared ignoring case, unless
+    /// [`Self::case_sensitive`] is enabled. This has no effect with [`Self::strict_bools`].
+    ///
+    /// NB: Values are only given as booleans to types that accept any type (see
+    /// [`Self::with_type_inference`]) if they are `true` or `false`.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use serde::Deserialize;
+    /// #[derive(Deserialize)]
+    /// struct Config {
+    ///     debug: bool,
+    /// }
+    ///
+    /// let config: Config = envious::Config::new()
+    ///     .with_bool_spellings(["enabled"], ["disabled"])
+    ///     .build_from_iter([("debug", "Enabled")])
+    ///     .unwrap();
+    ///
+    /// assert!(config.debug);
+    /// ```
+    pub fn with_bool_spellings<T, F, S>(
+        &mut self,
+        true_spellings: T,
+        false_spellings: F,
+    ) -> &mut Self
+    where
+        T: IntoIterator<Item = S>,
+        F: IntoIterator<Item = S>,
+        S: Into<Cow<'a, str>>,
+    {
+        self.true_spellings = Some(true_spellings.into_iter().map(Into::into).collect());
+        self.false_spellings = Some(false_spellings.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Configures whether booleans have to be spelled exactly `true` or `false`.
+    ///
+    /// Defaults to `false`. If `true`, then the spellings configured via
+    /// [`Self::with_bool_spellings`] are ignored.
+    pub fn strict_bools(&mut self, strict_bools: bool) -> &mut Self {
+        self.strict_bools = strict_bools;
+        self
+    }
+
+    /// Parses the given value as a boolean, see [`Self::with_bool_spellings`]
+    pub(crate) fn parse_bool(&self, value: &str) -> Result<bool, String> {
+        if self.strict_bools {
+            return value
+                .parse()
+                .map_err(|error: std::str::ParseBoolError| error.to_string());
+        }
+
+        let matches = |spelling: &str| {
+            if self.case_sensitive {
+                spelling == value
+            } else {
+                spelling.eq_ignore_ascii_case(value)
+            }
+        };
+
+        if self.bool_spellings(true).any(matches) {
+            Ok(true)
+        } else if self.bool_spellings(false).any(matches) {
+            Ok(false)
+        } else {
+            let expected = self
+                .bool_spellings(true)
+                .chain(self.bool_spellings(false))
+                .map(|spelling| format!("`{}`", spelling))
+                .collect::<Vec<_>>();
+            Err(format!("expected one of {}", expected.join(", ")))
+        }
+    }
+
+    /// The spellings accepted for the given boolean, see [`Self::with_bool_spellings`]
+    fn bool_spellings(&self, value: bool) -> impl Iterator<Item = &str> {
+        let (spellings, default) = if value {
+            (&self.true_spellings, Self::DEFAULT_TRUE_SPELLINGS)
+        } else {
+            (&self.false_spellings, Self::DEFAULT_FALSE_SPELLINGS)
+        };
+
+        let configured = spellings.iter().flatten().map(AsRef::as_ref);
+        let default = default.into_iter().filter(move |_| spellings.is_none());
+        configured.chain(default)
+    }
+
+    /// The delimiter of sequences at the given path, see [`Self::with_list_delimiter`]
+    pub(crate) fn list_delimiter(&self, path: &[String]) -> Option<&str> {
+        self.list_delimiters
+            .iter()
+            .find(|(pattern, _)| self.matches_path(pattern, path))
+            .map(|(_, delimiter)| delimiter)
+            .or(self.list_delimiter.as_ref())
+            .map(|delimiter| delimiter.as_ref())
+            .filter(|delimiter| delimiter.is_empty().not())
+    }
+
+    /// Whether the given path, with its parts joined by `.`, points to the given path.
+    fn matches_path(&self, pattern: &str, path: &[String]) -> bool {
+        pattern.split('.').count() == path.len()
+            && pattern.split('.').zip(path).all(|(expected, name)| {
+                if self.case_sensitive {
+                    expected == name
+                } else {
+                    expected.eq_ignore_ascii_case(name)
+                }
+            })
+    }
+
+    /// The order in which types are tried when inferring the type of a value, if inference is
+    /// configured
+    pub(crate) fn inferred_types(&self) -> Option<&[InferredType]> {
+        self.inferred_types.as_deref()
+    }
+
+    /// Whether the given value is one of the configured null markers
+    pub(crate) fn is_null(&self, value: &str) -> bool {
+        self.null_markers.iter().any(|marker| marker == value)
+    }
+
     /// Parse a given `T: Deserialize` from environment variables.
     ///
     /// ## Example
@@ -199,7 +1225,14 @@ impl<'a> Config<'a> {
     {
         let values = iter.into_iter().map(|(k, v)| (k.into(), v.into()));
 
-        let values = values.filter_map(|(mut key, value)| {
+        let mut aliased = Vec::new();
+        let values = values.filter_map(|(name, value)| {
+            if self.alias_key(&name).is_some() {
+                aliased.push((name, Value::Simple(value)));
+                return None;
+            }
+
+            let mut key = name.clone();
             // When running case-insensitive we need to make sure that same key with varying casing
             // would be stored in the same place. The simplest way to do this is to enforce a specific
             // case.
@@ -208,55 +1241,574 @@ impl<'a> Config<'a> {
             }
             let value = Value::Simple(value);
 
-            if let Some(prefix) = &self.prefix {
-                // If case insensitive, then the prefix will need to match the new key case
-                let coerced_prefix;
-                let prefix = if self.case_sensitive {
-                    prefix.as_ref()
-                } else {
-                    coerced_prefix = prefix.to_ascii_lowercase();
-                    &coerced_prefix
-                };
-
-                let stripped_key = key.strip_prefix(prefix)?.to_owned();
-                Some((stripped_key, value))
-            } else {
-                Some((key, value))
+            if self.prefixes.is_empty() {
+                return Some((0, key, name, value));
             }
+
+            // The first prefix the key starts with is used, as it has the highest precedence
+            self.prefixes
+                .iter()
+                .enumerate()
+                .find_map(|(index, prefix)| {
+                    // If case insensitive, then the prefix will need to match the new key case
+                    let coerced_prefix;
+                    let prefix = if self.case_sensitive {
+                        prefix.as_ref()
+                    } else {
+                        coerced_prefix = prefix.to_ascii_lowercase();
+                        &coerced_prefix
+                    };
+
+                    let key = key.strip_prefix(prefix)?.to_owned();
+                    Some((index, key, name.clone(), value.clone()))
+                })
         });
+        let mut values = values.collect::<Vec<_>>();
+
+        let mut context = Context::default();
+        self.replace_deprecated_keys(&mut values, &mut context)?;
+
+        let values = self
+            .merge_prefixes(values)
+            .into_iter()
+            .map(|(key, name, value)| {
+                context.origins.insert(key.clone(), name);
+                (key, value)
+            })
+            .collect::<Vec<_>>();
+
+        let parser = self.create_parser(values, aliased, context)?;
+
+        if self.accumulate_errors {
+            parser.deserialize_accumulating()
+        } else {
+            let context = parser.context.clone();
+            let result = T::deserialize(parser);
 
-        let parser = self.create_parser(values)?;
+            // A typo usually also leaves a variable missing, so unknown variables are reported
+            // first, as they come with a suggestion
+            match context.unknown.take().into_iter().next() {
+                Some(error) => Err(error),
+                None => result,
+            }
+        }
+    }
+
+    /// Merges the given values read with different prefixes, leaving out those that are
+    /// overridden by a prefix with higher precedence, see [`Self::with_fallback_prefix`].
+    ///
+    /// The values are given with the index of their prefix, their key and their name.
+    fn merge_prefixes(
+        &self,
+        mut values: Vec<(usize, String, String, Value)>,
+    ) -> Vec<(String, String, Value)> {
+        if self.prefixes.len() < 2 {
+            return values
+                .into_iter()
+                .map(|(_, key, name, value)| (key, name, value))
+                .collect();
+        }
+
+        values.sort_by_key(|(index, ..)| *index);
 
-        T::deserialize(parser)
+        // Values only override those of later prefixes, so the keys of a prefix are only added
+        // once all of its values are merged
+        let mut overriding = BTreeSet::new();
+        let mut merged: Vec<(String, String, Value)> = Vec::with_capacity(values.len());
+        let mut values = values.into_iter().peekable();
+        while let Some(&(index, ..)) = values.peek() {
+            let start = merged.len();
+            while let Some((_, key, name, value)) = values.next_if(|(other, ..)| *other == index) {
+                if self.overlaps_any(&overriding, &key).not() {
+                    merged.push((key, name, value));
+                }
+            }
+            overriding.extend(merged[start..].iter().map(|(key, ..)| key.clone()));
+        }
+
+        merged
+    }
+
+    /// Whether any of the given keys is the same as the given one, or nested below or above it.
+    fn overlaps_any(&self, keys: &BTreeSet<String>, key: &str) -> bool {
+        let separator = self.separator();
+
+        let same_or_above = key
+            .match_indices(separator)
+            .map(|(end, _)| &key[..end])
+            .chain(std::iter::once(key))
+            .any(|other| keys.contains(other));
+
+        let nested = format!("{}{}", key, separator);
+        let below = matches!(
+            keys.range(nested.clone()..).next(),
+            Some(other) if other.starts_with(&nested)
+        );
+
+        same_or_above || below
+    }
+
+    /// Returns the name of the environment variable that would be read for the given key.
+    ///
+    /// When case insensitive, the name is upper-cased, as is the convention for environment
+    /// variables. The parts of the key are encoded again, see [`Self::with_key_encoding`].
+    pub(crate) fn variable_name(&self, key: &str) -> String {
+        self.prefixed_variable_name(0, key)
+    }
+
+    /// Returns the name of the environment variable for the given key with the prefix of the
+    /// given index, see [`Self::variable_name`]
+    fn prefixed_variable_name(&self, prefix: usize, key: &str) -> String {
+        let encoded;
+        let key = match &self.key_encoding {
+            Some(encoding) => {
+                encoded = key
+                    .split(self.separator())
+                    .map(|part| encoding.encode(part))
+                    .collect::<Vec<_>>()
+                    .join(self.separator());
+                &encoded
+            }
+            None => key,
+        };
+
+        let name = match self.prefixes.get(prefix) {
+            Some(prefix) => format!("{}{}", prefix, key),
+            None => key.to_owned(),
+        };
+
+        if self.case_sensitive {
+            name
+        } else {
+            name.to_ascii_uppercase()
+        }
+    }
+
+    /// The separator used to split environment variable names into paths.
+    pub(crate) fn separator(&self) -> &str {
+        &self.separator
+    }
+
+    /// Splits the given key after the longest of the given names it starts with, or at the first
+    /// separator if it starts with none of them, see [`Self::schema_guided`]
+    ///
+    /// A key that is rather a likely typo of a longer name is kept whole, so that it is reported
+    /// as unknown (with that name as suggestion) instead of being nested below the shorter name.
+    pub(crate) fn split_key<'k>(&self, key: &'k str, names: &[&str]) -> (&'k str, Option<&'k str>) {
+        let separator = self.separator();
+        let starts_with = |name: &str| match key.get(..name.len()) {
+            Some(head) if self.case_sensitive => head == name,
+            Some(head) => head.eq_ignore_ascii_case(name),
+            None => false,
+        };
+
+        let longest = names
+            .iter()
+            .filter(|name| starts_with(name))
+            .map(|name| name.len())
+            .filter(|&len| len == key.len() || key[len..].starts_with(separator))
+            .max();
+
+        let is_typo = |len: usize| {
+            let longer = names
+                .iter()
+                .filter(|name| name.len() > len)
+                .copied()
+                .collect::<Vec<_>>();
+            suggest(key, &longer).is_some()
+        };
+
+        match longest {
+            Some(len) if len == key.len() || is_typo(len) => (key, None),
+            Some(len) => (&key[..len], Some(&key[len + separator.len()..])),
+            None => match key.split_once(separator) {
+                Some((head, rest)) => (head, Some(rest)),
+                None => (key, None),
+            },
+        }
     }
 
     /// Creates a [`Parser`] from its various parts.
-    fn create_parser<I>(&self, iter: I) -> Result<Parser, EnvDeserializationError>
+    ///
+    /// The aliased variables are given by their full name, see [`Self::with_alias`].
+    fn create_parser<I>(
+        &self,
+        iter: I,
+        aliased: Vec<(String, Value)>,
+        mut context: Context,
+    ) -> Result<Parser<'_>, EnvDeserializationError>
     where
         I: IntoIterator<Item = (String, Value)>,
     {
         let mut base = Value::Map(vec![]);
 
-        for (key, value) in iter {
-            let path = key.split(self.separator.as_ref()).collect::<Vec<_>>();
+        let mut entries = iter.into_iter().collect::<Vec<_>>();
+        self.graft_aliases(&mut entries, aliased, &mut context);
 
-            if path.len() == 1 {
+        let mut entries = entries
+            .into_iter()
+            .map(|(key, value)| {
+                let parts = key
+                    .split(self.separator.as_ref())
+                    .map(|part| match &self.key_encoding {
+                        Some(encoding) => encoding.decode(part),
+                        None => part.to_owned(),
+                    })
+                    .collect::<Vec<_>>();
+
+                // The variables need to be found by the decoded keys
+                let key = if self.key_encoding.is_some() {
+                    let decoded = parts.join(self.separator.as_ref());
+                    if let Some(name) = context.origins.remove(&key) {
+                        context.origins.insert(decoded.clone(), name);
+                    }
+                    decoded
+                } else {
+                    key
+                };
+
+                (key, parts, value)
+            })
+            .collect::<Vec<_>>();
+
+        if self.has_inline_values() {
+            entries = self.expand_inline_values(entries, &mut context)?;
+        }
+
+        for (key, parts, value) in entries {
+            let path = parts.iter().map(String::as_str).collect::<Vec<_>>();
+
+            // The keys are only split once the types they are read into are known
+            if path.len() == 1 || self.schema_guided {
                 if let Value::Map(base) = &mut base {
                     base.push((key, value));
                 } else {
                     unreachable!()
                 }
             } else {
-                base.insert_at(&path, value)?;
+                let result = base.insert_at(&path, value).map_err(|error| {
+                    EnvDeserializationError::InVariable {
+                        variable: context
+                            .origins
+                            .get(&key)
+                            .cloned()
+                            .unwrap_or_else(|| self.variable_name(&key)),
+                        path: path.iter().map(|s| s.to_string()).collect(),
+                        source: Box::new(error),
+                    }
+                });
+
+                match result {
+                    Err(error) if self.accumulate_errors => {
+                        // The simple value the variable conflicts with is skipped, so that the
+                        // conflict is only reported once
+                        if let Some(conflicting) = base.simple_along(&path) {
+                            context.skipped.get_mut().push(conflicting);
+                        }
+                        context.errors.get_mut().push(error);
+                    }
+                    result => result?,
+                }
             }
         }
 
         Ok(Parser {
             config: self,
+            context: Rc::new(context),
+            key: String::new(),
+            path: vec![],
             current: base,
         })
     }
 
+    /// Returns the key the variable with the given name is an alias for, if any, see
+    /// [`Self::with_alias`]
+    fn alias_key(&self, name: &str) -> Option<(usize, &str)> {
+        self.aliases
+            .iter()
+            .position(|(alias, _)| {
+                if self.case_sensitive {
+                    alias == name
+                } else {
+                    alias.eq_ignore_ascii_case(name)
+                }
+            })
+            .map(|index| (index, self.aliases[index].1.as_ref()))
+    }
+
+    /// Adds the aliased variables to the given entries under the key they are an alias for.
+    ///
+    /// Aliases for keys that are already given are left out, as are aliases that were added
+    /// after another alias for the same key, see [`Self::with_alias`].
+    fn graft_aliases(
+        &self,
+        entries: &mut Vec<(String, Value)>,
+        aliased: Vec<(String, Value)>,
+        context: &mut Context,
+    ) {
+        let mut aliased = aliased
+            .into_iter()
+            .filter_map(|(name, value)| {
+                let (index, key) = self.alias_key(&name)?;
+                let key = if self.case_sensitive {
+                    key.to_owned()
+                } else {
+                    key.to_ascii_lowercase()
+                };
+                Some((index, key, name, value))
+            })
+            .collect::<Vec<_>>();
+        aliased.sort_by_key(|(index, ..)| *index);
+
+        // Variables giving the key, or keys nested below or above it, take precedence
+        let mut overriding = entries
+            .iter()
+            .map(|(key, _)| key.clone())
+            .collect::<BTreeSet<_>>();
+
+        for (_, key, name, value) in aliased {
+            if self.overlaps_any(&overriding, &key) {
+                continue;
+            }
+
+            overriding.insert(key.clone());
+            context.origins.insert(key.clone(), name);
+            entries.push((key, value));
+        }
+    }
+
+    /// Replaces the deprecated keys of the given values with their replacement, see
+    /// [`Self::with_deprecated_key`]
+    ///
+    /// The values are given with the index of their prefix, their key and their name, before the
+    /// values of different prefixes are merged.
+    fn replace_deprecated_keys(
+        &self,
+        values: &mut Vec<(usize, String, String, Value)>,
+        context: &mut Context,
+    ) -> Result<(), EnvDeserializationError> {
+        if self.deprecated_keys.is_empty() {
+            return Ok(());
+        }
+
+        // The position of the longest deprecated key the given key is or starts with, and the
+        // length of that key
+        let deprecated_key = |key: &str| {
+            self.deprecated_keys
+                .iter()
+                .enumerate()
+                .filter(|(_, deprecated)| {
+                    let len = deprecated.key.len();
+                    let matches = match key.get(..len) {
+                        Some(head) if self.case_sensitive => head == deprecated.key,
+                        Some(head) => head.eq_ignore_ascii_case(&deprecated.key),
+                        None => false,
+                    };
+                    matches && (len == key.len() || key[len..].starts_with(self.separator()))
+                })
+                .map(|(position, deprecated)| (position, deprecated.key.len()))
+                .max_by_key(|(_, len)| *len)
+        };
+
+        let mut deprecated = vec![];
+        let mut index = 0;
+        while index < values.len() {
+            match deprecated_key(&values[index].1) {
+                Some((position, len)) => deprecated.push((position, len, values.remove(index))),
+                None => index += 1,
+            }
+        }
+        deprecated.sort_by_key(|(position, ..)| *position);
+
+        // Within a prefix, values given for a deprecated key only override those of the keys
+        // deprecated after it. Across prefixes, their precedence decides, see
+        // `Self::merge_prefixes`
+        let mut overriding = BTreeMap::<usize, BTreeSet<String>>::new();
+        for (prefix, key, ..) in values.iter() {
+            overriding.entry(*prefix).or_default().insert(key.clone());
+        }
+
+        let now = SystemTime::now();
+        let mut deprecated = deprecated.into_iter().peekable();
+        while let Some(&(position, ..)) = deprecated.peek() {
+            let DeprecatedKey {
+                replacement,
+                cutoff,
+                ..
+            } = &self.deprecated_keys[position];
+
+            let start = values.len();
+            while let Some((_, len, (prefix, key, variable, value))) =
+                deprecated.next_if(|(other, ..)| *other == position)
+            {
+                let replacement = format!("{}{}", replacement, &key[len..]);
+                let replacement = if self.case_sensitive {
+                    replacement
+                } else {
+                    replacement.to_ascii_lowercase()
+                };
+                let replacement_variable = self.prefixed_variable_name(prefix, &replacement);
+
+                if matches!(cutoff, Some(cutoff) if now >= *cutoff) {
+                    let error = EnvDeserializationError::DeprecatedVariable {
+                        variable,
+                        replacement: replacement_variable,
+                    };
+                    if self.accumulate_errors {
+                        context.errors.get_mut().push(error);
+                        continue;
+                    }
+                    return Err(error);
+                }
+
+                match &self.on_deprecated {
+                    Some(DeprecationCallback(callback)) => {
+                        callback(&variable, &replacement_variable)
+                    }
+                    None => warn_deprecated(&variable, &replacement_variable),
+                }
+
+                let overridden = matches!(
+                    overriding.get(&prefix),
+                    Some(keys) if self.overlaps_any(keys, &replacement)
+                );
+                if overridden.not() {
+                    values.push((prefix, replacement, variable, value));
+                }
+            }
+
+            for (prefix, key, ..) in &values[start..] {
+                overriding.entry(*prefix).or_default().insert(key.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether values can contain nested values themselves, i.e. inline maps or JSON
+    fn has_inline_values(&self) -> bool {
+        #[cfg(feature = "json")]
+        if self.json_values {
+            return true;
+        }
+
+        self.inline_map_separators().is_some()
+    }
+
+    /// Splits the given value into the nested values it contains, given by their path relative to
+    /// it, see [`Self::with_inline_maps`]
+    fn inline_values(&self, value: &str) -> Option<Result<NestedValues, EnvDeserializationError>> {
+        if let Some(json) = self.json_value(value) {
+            return Some(json.map(Value::into_leaves));
+        }
+
+        // Values without a key are more likely a mistake next to the nested values, which is
+        // reported as invalid nesting
+        let (_, key_value_separator) = self.inline_map_separators()?;
+        if value.contains(key_value_separator).not() {
+            return None;
+        }
+
+        self.inline_map_entries(value).map(|entries| {
+            entries.map(|entries| {
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (vec![key], value))
+                    .collect()
+            })
+        })
+    }
+
+    /// Replaces the values that are also nested further with the nested values they contain, see
+    /// [`Self::with_inline_maps`].
+    ///
+    /// Nested values that are also given in their own variable are left out, so that these take
+    /// precedence.
+    fn expand_inline_values(
+        &self,
+        entries: Vec<(String, Vec<String>, Value)>,
+        context: &mut Context,
+    ) -> Result<Vec<(String, Vec<String>, Value)>, EnvDeserializationError> {
+        let paths = entries
+            .iter()
+            .map(|(_, parts, _)| parts.clone())
+            .collect::<Vec<_>>();
+        // Whether the given path starts with the given parts
+        let starts_with = |path: &[String], parts: &[String]| {
+            path.len() >= parts.len()
+                && parts.iter().zip(path).all(|(part, other)| {
+                    if self.case_sensitive {
+                        part == other
+                    } else {
+                        part.eq_ignore_ascii_case(other)
+                    }
+                })
+        };
+
+        let mut expanded = Vec::with_capacity(entries.len());
+        for (key, parts, value) in entries {
+            let inline = match value {
+                Value::Simple(inline)
+                    if paths
+                        .iter()
+                        .any(|other| other.len() > parts.len() && starts_with(other, &parts)) =>
+                {
+                    inline
+                }
+                value => {
+                    expanded.push((key, parts, value));
+                    continue;
+                }
+            };
+
+            let variable = context
+                .origins
+                .get(&key)
+                .cloned()
+                .unwrap_or_else(|| self.variable_name(&key));
+
+            let inline_values = match self.inline_values(&inline) {
+                Some(Ok(inline_values)) => inline_values,
+                Some(Err(error)) => {
+                    let error = EnvDeserializationError::InVariable {
+                        variable,
+                        path: parts,
+                        source: Box::new(error),
+                    };
+
+                    if self.accumulate_errors {
+                        context.errors.get_mut().push(error);
+                        continue;
+                    }
+                    return Err(error);
+                }
+                // Reported as invalid nesting later on
+                None => {
+                    expanded.push((key, parts, Value::Simple(inline)));
+                    continue;
+                }
+            };
+
+            for (path, value) in inline_values {
+                let nested = parts.iter().cloned().chain(path).collect::<Vec<_>>();
+
+                let overridden = paths.iter().any(|other| {
+                    other.len() > parts.len()
+                        && (starts_with(other, &nested) || starts_with(&nested, other))
+                });
+                if overridden {
+                    continue;
+                }
+
+                let nested_key = nested.join(self.separator.as_ref());
+                context.origins.insert(nested_key.clone(), variable.clone());
+                expanded.push((nested_key, nested, Value::Simple(value)));
+            }
+        }
+
+        Ok(expanded)
+    }
+
     /// Given an iterator of keys and values, and a list of keys with corrected casing, converts
     /// the keys to the desired cases, thereby making the process case insensitive.
     ///
@@ -288,9 +1840,33 @@ impl<'a> Config<'a> {
     }
 }
 
+/// Warns about the use of a deprecated variable with the enabled logging crates, if any, see
+/// [`Config::on_deprecated`]
+#[cfg_attr(
+    not(any(feature = "log", feature = "tracing")),
+    allow(unused_variables)
+)]
+fn warn_deprecated(variable: &str, replacement: &str) {
+    #[cfg(feature = "log")]
+    log::warn!(
+        "The environment variable `{}` is deprecated, use `{}` instead",
+        variable,
+        replacement
+    );
+
+    #[cfg(feature = "tracing")]
+    tracing::warn!(
+        variable,
+        replacement,
+        "The environment variable `{}` is deprecated, use `{}` instead",
+        variable,
+        replacement
+    );
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{Config, Value};
+    use super::{Config, Context, Value};
 
     #[test]
     fn convert_list_of_key_vals_to_tree() {
@@ -314,7 +1890,9 @@ mod tests {
         ]);
 
         let config = Config::new();
-        let actual = config.create_parser(input).unwrap();
+        let actual = config
+            .create_parser(input, vec![], Context::default())
+            .unwrap();
 
         assert_eq!(actual.current, expected);
     }
@@ -341,7 +1919,10 @@ mod tests {
         ]);
 
         let mut config = Config::new();
-        let actual = config.with_separator("#").create_parser(input).unwrap();
+        let actual = config
+            .with_separator("#")
+            .create_parser(input, vec![], Context::default())
+            .unwrap();
 
         assert_eq!(actual.current, expected);
     }
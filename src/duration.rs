@@ -0,0 +1,135 @@
+use std::ops::Not;
+use std::time::Duration;
+
+use crate::error::EnvDeserializationError;
+
+/// The name and fields serde uses to deserialize a [`Duration`]
+pub(crate) const NAME: &str = "Duration";
+pub(crate) const FIELDS: &[&str] = &["secs", "nanos"];
+
+/// The units a duration can be given in, with their length in nanoseconds
+const UNITS: &[(&str, u128)] = &[
+    ("ns", 1),
+    ("us", 1_000),
+    ("µs", 1_000),
+    ("ms", 1_000_000),
+    ("s", 1_000_000_000),
+    ("min", 60 * 1_000_000_000),
+    ("m", 60 * 1_000_000_000),
+    ("h", 60 * 60 * 1_000_000_000),
+    ("d", 24 * 60 * 60 * 1_000_000_000),
+];
+
+/// Parses a human readable duration like `5s`, `250ms`, `1h30m` or `1.5` (seconds).
+pub(crate) fn parse(value: &str) -> Result<Duration, EnvDeserializationError> {
+    let invalid = || EnvDeserializationError::InvalidDuration(value.to_owned());
+
+    let mut rest = value.trim();
+    if rest.is_empty() {
+        return Err(invalid());
+    }
+
+    // A plain number is given in seconds
+    if rest
+        .bytes()
+        .all(|byte| byte.is_ascii_digit() || byte == b'.')
+    {
+        return to_duration(parse_number(rest, 1_000_000_000).ok_or_else(invalid)?)
+            .ok_or_else(invalid);
+    }
+
+    let mut nanos: u128 = 0;
+    while rest.is_empty().not() {
+        let number_len = rest
+            .find(|c: char| (c.is_ascii_digit() || c == '.').not())
+            .unwrap_or(rest.len());
+        let (number, remaining) = rest.split_at(number_len);
+
+        let unit_len = remaining
+            .find(|c: char| c.is_ascii_digit() || c.is_whitespace())
+            .unwrap_or(remaining.len());
+        let (unit, remaining) = remaining.split_at(unit_len);
+
+        let (_, unit) = UNITS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(unit))
+            .ok_or_else(invalid)?;
+
+        nanos = nanos
+            .checked_add(parse_number(number, *unit).ok_or_else(invalid)?)
+            .ok_or_else(invalid)?;
+        rest = remaining.trim_start();
+    }
+
+    to_duration(nanos).ok_or_else(invalid)
+}
+
+/// Parses a non-negative decimal number, returning it multiplied by the given unit.
+fn parse_number(number: &str, unit: u128) -> Option<u128> {
+    let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if integer.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    if number
+        .bytes()
+        .all(|byte| byte.is_ascii_digit() || byte == b'.')
+        .not()
+    {
+        return None;
+    }
+
+    let integer = match integer {
+        "" => 0,
+        integer => integer.parse::<u128>().ok()?.checked_mul(unit)?,
+    };
+
+    // Digits beyond nanosecond precision of the largest unit do not change the result
+    let fraction = &fraction[..fraction.len().min(18)];
+    let fraction = match fraction {
+        "" => 0,
+        fraction => fraction.parse::<u128>().ok()? * unit / 10u128.pow(fraction.len() as u32),
+    };
+
+    integer.checked_add(fraction)
+}
+
+fn to_duration(nanos: u128) -> Option<Duration> {
+    let secs = u64::try_from(nanos / 1_000_000_000).ok()?;
+    Some(Duration::new(secs, (nanos % 1_000_000_000) as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::parse;
+
+    #[test]
+    fn parse_durations() {
+        assert_eq!(parse("5s"), Ok(Duration::from_secs(5)));
+        assert_eq!(parse("250ms"), Ok(Duration::from_millis(250)));
+        assert_eq!(parse("1h30m"), Ok(Duration::from_secs(90 * 60)));
+        assert_eq!(parse("1h 30min 5S"), Ok(Duration::from_secs(90 * 60 + 5)));
+        assert_eq!(parse("1.5"), Ok(Duration::from_millis(1500)));
+        assert_eq!(parse("0.5h"), Ok(Duration::from_secs(30 * 60)));
+        assert_eq!(parse("2d"), Ok(Duration::from_secs(2 * 24 * 60 * 60)));
+        assert_eq!(parse("10us 7ns"), Ok(Duration::from_nanos(10_007)));
+        assert_eq!(parse("0"), Ok(Duration::ZERO));
+    }
+
+    #[test]
+    fn reject_invalid_durations() {
+        for value in [
+            "",
+            "s",
+            "5x",
+            "-5s",
+            "1..5s",
+            ".",
+            "5s 3",
+            "99999999999999999999999d",
+        ] {
+            assert!(parse(value).is_err(), "{:?} should be rejected", value);
+        }
+    }
+}
@@ -17,6 +17,96 @@ pub enum EnvDeserializationError {
     /// Invalid nesting detected for the given paths ending in the given array
     #[error("Invalid nesting detected for paths ending in: {:?}", .0)]
     InvalidEnvNesting(Vec<String>),
+
+    /// An array index was given that is not a non-negative integer, see
+    /// [`crate::Config::dense_arrays`]
+    #[error("`{}` is not a valid array index, expected a non-negative integer", .0)]
+    InvalidArrayIndex(String),
+
+    /// The same array index was given more than once, see [`crate::Config::dense_arrays`]
+    #[error("The array index {} is given more than once", .0)]
+    DuplicateArrayIndex(usize),
+
+    /// An entry of a map given in a single variable has no key, see
+    /// [`crate::Config::with_inline_maps`]
+    #[error("`{}` is not a valid map entry, expected a key and a value", .0)]
+    InvalidMapEntry(String),
+
+    /// A value given as JSON could not be parsed, see `Config::json_values` (requires the `json`
+    /// feature)
+    #[error("Invalid JSON: {}", .0)]
+    InvalidJson(String),
+
+    /// A duration was given in an invalid format
+    #[error("`{}` is not a valid duration, expected e.g. `5s`, `250ms`, `1h30m` or `1.5`", .0)]
+    InvalidDuration(String),
+
+    /// A timestamp was given in an invalid format, or is before the Unix epoch
+    #[error("`{}` is not a valid timestamp, expected e.g. `2024-03-01T12:00:00Z` or `1709294400`", .0)]
+    InvalidTimestamp(String),
+
+    /// Binary data could not be decoded, see [`crate::Config::with_binary_encoding`]
+    ///
+    /// Only the reason is given, as the value is usually a secret.
+    #[error("Invalid binary data: {}", .0)]
+    InvalidBinary(String),
+
+    /// A deprecated environment variable was set after its cutoff, see
+    /// [`crate::Config::with_deprecated_key_until`]
+    #[error(
+        "The environment variable `{variable}` is no longer supported, use `{replacement}` instead"
+    )]
+    DeprecatedVariable {
+        /// The full name of the deprecated environment variable, as it was given
+        variable: String,
+        /// The full name of the environment variable to use instead
+        replacement: String,
+    },
+
+    /// A field required by the deserialized type was not given
+    ///
+    /// Missing fields are usually reported as [`Self::MissingVariable`] instead.
+    #[error("Missing field `{}`", .0)]
+    MissingField(String),
+
+    /// An environment variable required by the deserialized type was not set
+    #[error("Missing environment variable `{variable}` (for `{}`)", .path.join("."))]
+    MissingVariable {
+        /// The full name of the environment variable that needs to be set, including any prefix
+        variable: String,
+        /// The path of fields, keys and variants leading to the missing value
+        path: Vec<String>,
+    },
+
+    /// Several errors occurred, see [`crate::Config::accumulate_errors`]
+    #[error("{} errors occurred during deserialization:{}", .0.len(), display_list(.0))]
+    Multiple(Vec<EnvDeserializationError>),
+
+    /// An environment variable was set that does not correspond to any field, see
+    /// [`crate::Config::strict`]
+    #[error("Unknown environment variable `{variable}`{}", display_suggestion(.suggestion))]
+    UnknownVariable {
+        /// The full name of the environment variable, including any prefix, as it was given
+        variable: String,
+        /// The path of fields, keys and variants the variable was read as
+        path: Vec<String>,
+        /// The name of the closest known field or variant, if any
+        suggestion: Option<String>,
+    },
+
+    /// An error occurred while deserializing a specific environment variable
+    ///
+    /// For values that are nested further (i.e. structs, maps, sequences and complex enums) the
+    /// `variable` is the common part of all the environment variables making up that value.
+    #[error("Could not deserialize `{variable}` (at `{}`): {source}", .path.join("."))]
+    InVariable {
+        /// The full name of the environment variable, including any prefix, as it was given
+        variable: String,
+        /// The path of fields, keys and variants leading to the value that failed
+        path: Vec<String>,
+        /// The underlying error
+        source: Box<EnvDeserializationError>,
+    },
 }
 
 impl serde::de::Error for EnvDeserializationError {
@@ -26,4 +116,22 @@ impl serde::de::Error for EnvDeserializationError {
     {
         Self::GenericDeserialization(msg.to_string())
     }
+
+    fn missing_field(field: &'static str) -> Self {
+        Self::MissingField(field.to_owned())
+    }
+}
+
+fn display_list(errors: &[EnvDeserializationError]) -> String {
+    errors
+        .iter()
+        .map(|error| format!("\n- {}", error))
+        .collect()
+}
+
+fn display_suggestion(suggestion: &Option<String>) -> String {
+    match suggestion {
+        Some(suggestion) => format!(", did you mean `{}`?", suggestion),
+        None => String::new(),
+    }
 }
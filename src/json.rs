@@ -0,0 +1,48 @@
+use std::ops::Not;
+
+use crate::error::EnvDeserializationError;
+use crate::{Config, Value};
+
+/// Parses the given value as JSON, if it looks like a JSON object or array, see
+/// [`Config::json_values`]
+pub(crate) fn parse(
+    config: &Config<'_>,
+    value: &str,
+) -> Option<Result<Value, EnvDeserializationError>> {
+    if value.trim_start().starts_with(['{', '[']).not() {
+        return None;
+    }
+
+    let result = serde_json::from_str::<serde_json::Value>(value)
+        .map(|json| convert(config, json).unwrap_or(Value::Map(vec![])))
+        .map_err(|error| EnvDeserializationError::InvalidJson(error.to_string()));
+
+    Some(result)
+}
+
+/// Converts the given JSON into a [`Value`], with arrays being maps of their indices.
+///
+/// `null` is converted into the first null marker, or left out if there is none.
+fn convert(config: &Config<'_>, json: serde_json::Value) -> Option<Value> {
+    let value = match json {
+        serde_json::Value::Null => Value::Simple(config.null_markers.first()?.to_string()),
+        serde_json::Value::Bool(value) => Value::Simple(value.to_string()),
+        serde_json::Value::Number(value) => Value::Simple(value.to_string()),
+        serde_json::Value::String(value) => Value::Simple(value),
+        serde_json::Value::Array(values) => Value::Map(
+            values
+                .into_iter()
+                .enumerate()
+                .filter_map(|(index, value)| Some((index.to_string(), convert(config, value)?)))
+                .collect(),
+        ),
+        serde_json::Value::Object(values) => Value::Map(
+            values
+                .into_iter()
+                .filter_map(|(key, value)| Some((key, convert(config, value)?)))
+                .collect(),
+        ),
+    };
+
+    Some(value)
+}
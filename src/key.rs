@@ -0,0 +1,290 @@
+use std::ops::Not;
+
+use serde::de::{IntoDeserializer, Unexpected};
+use serde::Deserializer;
+
+use crate::error::EnvDeserializationError;
+use crate::Config;
+
+/// How characters that cannot be part of environment variable names are written in keys, see
+/// [`Config::with_key_encoding`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyEncoding {
+    /// The hexadecimal UTF-8 bytes of a character, each following `_x`, e.g. `api_x2Eexample_x2Ecom`
+    /// for `api.example.com`
+    Hex,
+    /// The hexadecimal UTF-8 bytes of a character, each following a `%`, e.g. `api%2Eexample%2Ecom`
+    /// for `api.example.com`
+    Percent,
+}
+
+impl KeyEncoding {
+    /// Encodes all characters of the given key other than ASCII letters, digits and `_`.
+    ///
+    /// A `_` is only kept as it is if an ASCII letter or digit follows it, as it could otherwise
+    /// form a separator like `__` with its neighbours, or start an escape sequence with
+    /// [`Self::Hex`]. E.g. `a_x41_` is encoded as `a_x5Fx41_x5F`.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use envious::KeyEncoding;
+    /// assert_eq!(KeyEncoding::Hex.encode("api.example.com"), "api_x2Eexample_x2Ecom");
+    /// assert_eq!(KeyEncoding::Hex.encode("a..b"), "a_x2E_x2Eb");
+    /// assert_eq!(KeyEncoding::Percent.encode("my-key"), "my%2Dkey");
+    /// ```
+    pub fn encode(&self, key: &str) -> String {
+        let mut encoded = String::with_capacity(key.len());
+
+        let mut chars = key.chars().peekable();
+        while let Some(c) = chars.next() {
+            let keep = match c {
+                '_' => matches!(
+                    chars.peek(),
+                    Some(next) if next.is_ascii_alphanumeric()
+                        && (*self == Self::Hex && next.eq_ignore_ascii_case(&'x')).not()
+                ),
+                c => c.is_ascii_alphanumeric(),
+            };
+            if keep {
+                encoded.push(c);
+                continue;
+            }
+
+            for byte in c.to_string().bytes() {
+                encoded.push_str(&format!("{}{:02X}", self.escape(), byte));
+            }
+        }
+
+        encoded
+    }
+
+    /// Decodes the given key, which is part of an environment variable name.
+    ///
+    /// Anything that is not a valid escape sequence is kept as it is. Escape sequences are
+    /// decoded case insensitively, as keys are lower-cased when running case insensitive.
+    pub fn decode(&self, key: &str) -> String {
+        let escape = self.escape().as_bytes();
+        let mut bytes = Vec::with_capacity(key.len());
+        let mut rest = key.as_bytes();
+
+        while let Some((&byte, remaining)) = rest.split_first() {
+            let escaped = rest
+                .get(..escape.len() + 2)
+                .filter(|sequence| sequence[..escape.len()].eq_ignore_ascii_case(escape))
+                .map(|sequence| &sequence[escape.len()..])
+                .filter(|digits| digits.iter().all(u8::is_ascii_hexdigit))
+                .and_then(|digits| std::str::from_utf8(digits).ok())
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok());
+
+            match escaped {
+                Some(escaped) => {
+                    bytes.push(escaped);
+                    rest = &rest[escape.len() + 2..];
+                }
+                None => {
+                    bytes.push(byte);
+                    rest = remaining;
+                }
+            }
+        }
+
+        String::from_utf8(bytes).unwrap_or_else(|_| key.to_owned())
+    }
+
+    /// The characters each escaped byte follows
+    fn escape(&self) -> &'static str {
+        match self {
+            Self::Hex => "_x",
+            Self::Percent => "%",
+        }
+    }
+}
+
+/// A deserializer for the keys of maps, i.e. a single part of an environment variable name.
+///
+/// Besides strings, keys can be numbers (e.g. `listeners__8080__host`), booleans (see
+/// [`Config::with_bool_spellings`]) or enum variants, which are matched case insensitively unless
+/// configured otherwise.
+pub(crate) struct Key<'a> {
+    config: &'a Config<'a>,
+    key: String,
+}
+
+impl<'a> Key<'a> {
+    pub(crate) fn new(config: &'a Config<'a>, key: String) -> Self {
+        Self { config, key }
+    }
+}
+
+macro_rules! forward_parsed {
+    ($($ty:ident => $method:ident => $visit:ident),* $(,)?) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+                where V: serde::de::Visitor<'de>
+            {
+                match self.key.parse::<$ty>() {
+                    Ok(key) => visitor.$visit(key),
+                    Err(e) => Err(EnvDeserializationError::GenericDeserialization(format!(
+                        "'{}' could not be deserialized due to: {}",
+                        self.key, e
+                    ))),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Key<'de> {
+    type Error = EnvDeserializationError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_string(self.key)
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        match self.config.parse_bool(&self.key) {
+            Ok(key) => visitor.visit_bool(key),
+            Err(reason) => Err(EnvDeserializationError::GenericDeserialization(format!(
+                "'{}' could not be deserialized due to: {}",
+                self.key, reason
+            ))),
+        }
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        let mut chars = self.key.chars();
+        match (chars.next(), chars.next()) {
+            (Some(key), None) => visitor.visit_char(key),
+            _ => Err(serde::de::Error::invalid_value(
+                Unexpected::Str(&self.key),
+                &visitor,
+            )),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        let key = self
+            .config
+            .maybe_coerce_case([(self.key, ())], variants)
+            .map(|(key, ())| key)
+            .next()
+            .expect("a single key is given");
+
+        visitor.visit_enum(key.into_deserializer())
+    }
+
+    forward_parsed! {
+        u8 => deserialize_u8 => visit_u8,
+        i8 => deserialize_i8 => visit_i8,
+        u16 => deserialize_u16 => visit_u16,
+        i16 => deserialize_i16 => visit_i16,
+        u32 => deserialize_u32 => visit_u32,
+        i32 => deserialize_i32 => visit_i32,
+        u64 => deserialize_u64 => visit_u64,
+        i64 => deserialize_i64 => visit_i64,
+        u128 => deserialize_u128 => visit_u128,
+        i128 => deserialize_i128 => visit_i128,
+        f32 => deserialize_f32 => visit_f32,
+        f64 => deserialize_f64 => visit_f64,
+    }
+
+    serde::forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier
+        ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Not;
+
+    use super::KeyEncoding;
+
+    #[test]
+    fn hex_round_trip() {
+        for key in [
+            "api.example.com",
+            "/var/lib",
+            "kebab-case",
+            "ünïcödé",
+            "plain_key",
+        ] {
+            let encoded = KeyEncoding::Hex.encode(key);
+            assert_eq!(KeyEncoding::Hex.decode(&encoded), key);
+            assert_eq!(KeyEncoding::Hex.decode(&encoded.to_ascii_lowercase()), key);
+        }
+
+        assert_eq!(KeyEncoding::Hex.decode("a_xZZ_b_x_c_"), "a_xZZ_b_x_c_");
+    }
+
+    #[test]
+    fn hex_round_trip_of_escape_sequences() {
+        for key in [
+            "a_x41_", "_x2e_", "__x", "x_x_x", "a..b", "a_.b", "a._b", "a__b",
+        ] {
+            let encoded = KeyEncoding::Hex.encode(key);
+            assert!(encoded.contains("__").not(), "{} contains `__`", encoded);
+            assert_eq!(KeyEncoding::Hex.decode(&encoded), key);
+            assert_eq!(KeyEncoding::Hex.decode(&encoded.to_ascii_lowercase()), key);
+        }
+
+        assert_eq!(KeyEncoding::Hex.encode("a_x41_"), "a_x5Fx41_x5F");
+    }
+
+    #[test]
+    fn percent_round_trip() {
+        for key in [
+            "api.example.com",
+            "/var/lib",
+            "kebab-case",
+            "ünïcödé",
+            "plain_key",
+        ] {
+            let encoded = KeyEncoding::Percent.encode(key);
+            assert_eq!(KeyEncoding::Percent.decode(&encoded), key);
+            assert_eq!(
+                KeyEncoding::Percent.decode(&encoded.to_ascii_lowercase()),
+                key
+            );
+        }
+
+        assert_eq!(KeyEncoding::Percent.decode("100%"), "100%");
+        assert_eq!(KeyEncoding::Percent.decode("%FF"), "%FF");
+    }
+}
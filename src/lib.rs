@@ -3,12 +3,22 @@
 
 use value::Value;
 
+mod binary;
 mod config;
+mod duration;
 mod error;
+#[cfg(feature = "json")]
+mod json;
+mod key;
+mod parse;
+mod placeholder;
+mod timestamp;
 mod value;
 
-pub use config::Config;
+pub use binary::BinaryEncoding;
+pub use config::{Config, InferredType};
 pub use error::EnvDeserializationError;
+pub use key::KeyEncoding;
 
 #[cfg(test)]
 mod test {
@@ -0,0 +1,257 @@
+use std::ops::Not;
+
+use crate::error::EnvDeserializationError;
+use crate::Config;
+
+/// Types that are parsed from the value of a single variable
+pub(crate) trait ParseValue: Sized {
+    /// Parses the given value of the variable at the given path
+    fn parse_value(
+        value: &str,
+        config: &Config<'_>,
+        path: &[String],
+    ) -> Result<Self, EnvDeserializationError>;
+}
+
+fn invalid(value: &str, reason: impl std::fmt::Display) -> EnvDeserializationError {
+    EnvDeserializationError::GenericDeserialization(format!(
+        "'{}' could not be deserialized due to: {}",
+        value, reason
+    ))
+}
+
+macro_rules! parse_float {
+    ($($ty:ident),* $(,)?) => {
+        $(
+            impl ParseValue for $ty {
+                fn parse_value(
+                    value: &str,
+                    config: &Config<'_>,
+                    _path: &[String],
+                ) -> Result<Self, EnvDeserializationError> {
+                    let parsed = if config.numeric_literals {
+                        value.trim().replace('_', "").parse()
+                    } else {
+                        value.parse()
+                    };
+
+                    parsed.map_err(|error| invalid(value, error))
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! parse_integer {
+    ($($ty:ident),* $(,)?) => {
+        $(
+            impl ParseValue for $ty {
+                fn parse_value(
+                    value: &str,
+                    config: &Config<'_>,
+                    path: &[String],
+                ) -> Result<Self, EnvDeserializationError> {
+                    let parsed = match normalize_integer(value) {
+                        Some((digits, radix)) if config.numeric_literals => {
+                            $ty::from_str_radix(&digits, radix)
+                        }
+                        _ => value.parse(),
+                    };
+
+                    match parsed {
+                        Ok(parsed) => Ok(parsed),
+                        Err(_) if config.allows_unit_suffixes(path) => {
+                            let parsed = parse_with_unit(value).map_err(|reason| invalid(value, reason))?;
+                            $ty::try_from(parsed).map_err(|_| {
+                                invalid(value, concat!("number does not fit into ", stringify!($ty)))
+                            })
+                        }
+                        Err(error) => Err(invalid(value, error)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+parse_float!(f32, f64);
+parse_integer!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128);
+
+impl ParseValue for bool {
+    fn parse_value(
+        value: &str,
+        config: &Config<'_>,
+        _path: &[String],
+    ) -> Result<Self, EnvDeserializationError> {
+        config
+            .parse_bool(value)
+            .map_err(|reason| invalid(value, reason))
+    }
+}
+
+/// Splits an integer literal like `0xFF`, `+1_000` or `-0b1010` into its sign and digits, and its
+/// radix, see [`Config::numeric_literals`]
+///
+/// Returns `None` if the value does not consist of anything but a sign and a radix prefix.
+fn normalize_integer(value: &str) -> Option<(String, u32)> {
+    let value = value.trim();
+    let (sign, value) = match value.strip_prefix('-') {
+        Some(value) => ("-", value),
+        None => ("", value.strip_prefix('+').unwrap_or(value)),
+    };
+
+    let (radix, digits) = [
+        ("0x", 16),
+        ("0X", 16),
+        ("0o", 8),
+        ("0O", 8),
+        ("0b", 2),
+        ("0B", 2),
+    ]
+    .into_iter()
+    .find_map(|(prefix, radix)| Some((radix, value.strip_prefix(prefix)?)))
+    .unwrap_or((10, value));
+
+    let digits = digits.replace('_', "");
+    // Signs are only allowed in front of the radix prefix
+    if digits.is_empty() || digits.starts_with(['+', '-']) {
+        return None;
+    }
+
+    Some((format!("{}{}", sign, digits), radix))
+}
+
+/// The units integers can be given in, with their multiplier, see [`Config::unit_suffixes`]
+const UNITS: &[(&str, i128)] = &[
+    ("", 1),
+    ("B", 1),
+    ("k", 1_000),
+    ("kB", 1_000),
+    ("M", 1_000_000),
+    ("MB", 1_000_000),
+    ("G", 1_000_000_000),
+    ("GB", 1_000_000_000),
+    ("T", 1_000_000_000_000),
+    ("TB", 1_000_000_000_000),
+    ("P", 1_000_000_000_000_000),
+    ("PB", 1_000_000_000_000_000),
+    ("E", 1_000_000_000_000_000_000),
+    ("EB", 1_000_000_000_000_000_000),
+    ("Ki", 1 << 10),
+    ("KiB", 1 << 10),
+    ("Mi", 1 << 20),
+    ("MiB", 1 << 20),
+    ("Gi", 1 << 30),
+    ("GiB", 1 << 30),
+    ("Ti", 1 << 40),
+    ("TiB", 1 << 40),
+    ("Pi", 1 << 50),
+    ("PiB", 1 << 50),
+    ("Ei", 1 << 60),
+    ("EiB", 1 << 60),
+];
+
+/// Parses an integer followed by a unit, e.g. `512MiB`, `10k` or `1.5GB`.
+///
+/// Returns the reason if the value cannot be parsed.
+fn parse_with_unit(value: &str) -> Result<i128, String> {
+    let value = value.trim();
+    let number_len = value
+        .find(|c: char| (c.is_ascii_digit() || matches!(c, '.' | '-' | '+')).not())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(number_len);
+    let unit = unit.trim_start();
+
+    let (_, multiplier) = UNITS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(unit))
+        .ok_or_else(|| format!("unknown unit `{}`", unit))?;
+
+    let (negative, number) = match number.strip_prefix('-') {
+        Some(number) => (true, number),
+        None => (false, number.strip_prefix('+').unwrap_or(number)),
+    };
+    let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+    let is_digits = |digits: &str| digits.bytes().all(|byte| byte.is_ascii_digit());
+    if integer.is_empty() || is_digits(integer).not() || is_digits(fraction).not() {
+        return Err(String::from("invalid number"));
+    }
+
+    let too_large = || String::from("number too large");
+    let mut parsed = integer
+        .parse::<i128>()
+        .map_err(|_| too_large())?
+        .checked_mul(*multiplier)
+        .ok_or_else(too_large)?;
+
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty().not() {
+        let scale = u32::try_from(fraction.len())
+            .ok()
+            .and_then(|len| 10i128.checked_pow(len))
+            .ok_or_else(|| String::from("too many decimal places"))?;
+        let fraction = fraction
+            .parse::<i128>()
+            .ok()
+            .and_then(|fraction| fraction.checked_mul(*multiplier))
+            .ok_or_else(too_large)?;
+        if fraction % scale != 0 {
+            return Err(String::from("not a whole number"));
+        }
+        parsed = parsed.checked_add(fraction / scale).ok_or_else(too_large)?;
+    }
+
+    Ok(if negative { -parsed } else { parsed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize_integer, parse_with_unit};
+
+    #[test]
+    fn normalize_integers() {
+        assert_eq!(normalize_integer("0xFF"), Some((String::from("FF"), 16)));
+        assert_eq!(
+            normalize_integer(" -0b1010 "),
+            Some((String::from("-1010"), 2))
+        );
+        assert_eq!(normalize_integer("+0o7_55"), Some((String::from("755"), 8)));
+        assert_eq!(
+            normalize_integer("1_000_000"),
+            Some((String::from("1000000"), 10))
+        );
+        assert_eq!(normalize_integer("0x"), None);
+        assert_eq!(normalize_integer("+-1"), None);
+        assert_eq!(normalize_integer("0x-1"), None);
+    }
+
+    #[test]
+    fn parse_units() {
+        assert_eq!(parse_with_unit("512MiB"), Ok(512 * 1024 * 1024));
+        assert_eq!(parse_with_unit("10k"), Ok(10_000));
+        assert_eq!(parse_with_unit("10 kb"), Ok(10_000));
+        assert_eq!(parse_with_unit("1.5GB"), Ok(1_500_000_000));
+        assert_eq!(parse_with_unit("1.5KiB"), Ok(1536));
+        assert_eq!(parse_with_unit("-2M"), Ok(-2_000_000));
+        assert_eq!(parse_with_unit("42B"), Ok(42));
+        assert_eq!(parse_with_unit("1.000"), Ok(1));
+    }
+
+    #[test]
+    fn reject_invalid_units() {
+        assert_eq!(
+            parse_with_unit("5XiB"),
+            Err(String::from("unknown unit `XiB`"))
+        );
+        assert_eq!(
+            parse_with_unit("1.5B"),
+            Err(String::from("not a whole number"))
+        );
+        assert_eq!(parse_with_unit("MiB"), Err(String::from("invalid number")));
+        assert_eq!(parse_with_unit("1-2k"), Err(String::from("invalid number")));
+        assert_eq!(
+            parse_with_unit("999999999999999999999999999EiB"),
+            Err(String::from("number too large"))
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use std::ops::Not;
+use std::time::Duration;
+
+use crate::duration;
+use crate::error::EnvDeserializationError;
+
+/// The name and fields serde uses to deserialize a [`std::time::SystemTime`]
+pub(crate) const NAME: &str = "SystemTime";
+pub(crate) const FIELDS: &[&str] = &["secs_since_epoch", "nanos_since_epoch"];
+
+/// Parses a timestamp given in RFC 3339 (e.g. `2024-03-01T12:00:00Z`), as a date (e.g.
+/// `2024-03-01`, meaning midnight UTC) or as seconds since the Unix epoch (e.g. `1709294400`).
+///
+/// Returns the time since the Unix epoch, as timestamps before it cannot be represented by serde.
+pub(crate) fn parse(value: &str) -> Result<Duration, EnvDeserializationError> {
+    let invalid = || EnvDeserializationError::InvalidTimestamp(value.to_owned());
+    let value = value.trim();
+
+    if value
+        .bytes()
+        .all(|byte| byte.is_ascii_digit() || byte == b'.')
+    {
+        return duration::parse(value).map_err(|_| invalid());
+    }
+
+    let (secs, nanos) = parse_rfc3339(value).ok_or_else(invalid)?;
+    let secs = u64::try_from(secs).map_err(|_| invalid())?;
+    Ok(Duration::new(secs, nanos))
+}
+
+/// Parses the given RFC 3339 timestamp into seconds and nanoseconds since the Unix epoch.
+fn parse_rfc3339(value: &str) -> Option<(i64, u32)> {
+    let year = digits(value.get(0..4)?)?;
+    let month = digits(value.get(5..7)?)?;
+    let day = digits(value.get(8..10)?)?;
+    if value.get(4..5)? != "-" || value.get(7..8)? != "-" {
+        return None;
+    }
+    if (1..=12).contains(&month).not() || (1..=days_in_month(year, month)).contains(&day).not() {
+        return None;
+    }
+
+    let days = days_since_epoch(year, month, day);
+    if value.len() == 10 {
+        return Some((days * 86_400, 0));
+    }
+
+    if matches!(value.get(10..11)?, "T" | "t" | " ").not() {
+        return None;
+    }
+
+    let hour = digits(value.get(11..13)?)?;
+    let minute = digits(value.get(14..16)?)?;
+    let second = digits(value.get(17..19)?)?;
+    if value.get(13..14)? != ":" || value.get(16..17)? != ":" {
+        return None;
+    }
+    // A second of 60 is a leap second
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let mut rest = value.get(19..)?;
+    let mut nanos = 0;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let len = fraction
+            .find(|c: char| c.is_ascii_digit().not())
+            .unwrap_or(fraction.len());
+        if len == 0 {
+            return None;
+        }
+
+        // Digits beyond nanosecond precision are cut off
+        let precise = &fraction[..len.min(9)];
+        nanos = digits(precise)? * 10u32.pow(9 - precise.len() as u32);
+        rest = &fraction[len..];
+    }
+
+    let offset = match rest {
+        "Z" | "z" => 0,
+        offset if offset.len() == 6 && offset.get(3..4)? == ":" => {
+            let hours = i64::from(digits(offset.get(1..3)?)?);
+            let minutes = i64::from(digits(offset.get(4..6)?)?);
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+
+            match offset.get(0..1)? {
+                "+" => hours * 3600 + minutes * 60,
+                "-" => -(hours * 3600 + minutes * 60),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+
+    let secs = days * 86_400 + i64::from(hour) * 3600 + i64::from(minute) * 60 + i64::from(second)
+        - offset;
+    Some((secs, nanos))
+}
+
+/// Parses the given string, as long as it only consists of ASCII digits
+fn digits(value: &str) -> Option<u32> {
+    if value.is_empty() || value.bytes().all(|byte| byte.is_ascii_digit()).not() {
+        return None;
+    }
+    value.parse().ok()
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        // Leap years are divisible by 4, but not by 100 unless also by 400
+        2 if matches!((year % 4, year % 100, year % 400), (0, 1.., _) | (_, _, 0)) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// The number of days between the Unix epoch and the given date of the Gregorian calendar.
+///
+/// See <http://howardhinnant.github.io/date_algorithms.html#days_from_civil>
+fn days_since_epoch(year: u32, month: u32, day: u32) -> i64 {
+    let year = i64::from(year) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::parse;
+
+    #[test]
+    fn parse_timestamps() {
+        assert_eq!(parse("1970-01-01T00:00:00Z"), Ok(Duration::ZERO));
+        assert_eq!(parse("1709294400"), Ok(Duration::from_secs(1_709_294_400)));
+        assert_eq!(parse("2024-03-01"), Ok(Duration::from_secs(1_709_251_200)));
+        assert_eq!(
+            parse("2024-03-01T12:00:00Z"),
+            Ok(Duration::from_secs(1_709_294_400))
+        );
+        assert_eq!(
+            parse("2024-03-01t14:30:00.25+02:30"),
+            Ok(Duration::new(1_709_294_400, 250_000_000))
+        );
+        assert_eq!(
+            parse("2024-02-29 07:00:00-05:00"),
+            Ok(Duration::from_secs(1_709_208_000))
+        );
+        assert_eq!(
+            parse("2000-01-01T00:00:00.123456789123Z"),
+            Ok(Duration::new(946_684_800, 123_456_789))
+        );
+    }
+
+    #[test]
+    fn reject_invalid_timestamps() {
+        for value in [
+            "",
+            "2024-13-01",
+            "2023-02-29",
+            "2024-03-01T12:00:00",
+            "2024-03-01T24:00:00Z",
+            "2024-03-01T12:00:00.Z",
+            "2024-03-01T12:00:00+0200",
+            "1969-12-31T23:59:59Z",
+            "2024/03/01",
+            "yesterday",
+        ] {
+            assert!(parse(value).is_err(), "{:?} should be rejected", value);
+        }
+    }
+}
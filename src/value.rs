@@ -1,24 +1,185 @@
-use std::ops::Not;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::ops::{Bound, Not};
+use std::rc::Rc;
 
-use serde::de::value::{MapAccessDeserializer, MapDeserializer, SeqDeserializer};
-use serde::de::IntoDeserializer;
+use serde::de::value::SeqDeserializer;
+use serde::de::{
+    DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    Unexpected, VariantAccess,
+};
 use serde::Deserializer;
 
+use crate::duration;
 use crate::error::EnvDeserializationError;
-use crate::Config;
+use crate::key::Key;
+use crate::parse::ParseValue;
+use crate::placeholder::Placeholder;
+use crate::timestamp;
+use crate::{Config, InferredType};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub(crate) enum Value {
     Simple(String),
     Map(Vec<(String, Value)>),
 }
 
+/// State shared between all [`Parser`]s deserializing the same set of environment variables
+#[derive(Debug, Default)]
+pub(crate) struct Context {
+    /// Maps the keys of the tree (joined with the separator) to the names of the environment
+    /// variables they were read from
+    pub(crate) origins: BTreeMap<String, String>,
+    /// Errors collected so far, see [`Config::accumulate_errors`]
+    pub(crate) errors: RefCell<Vec<EnvDeserializationError>>,
+    /// Variables that did not correspond to any field, see [`Config::strict`]
+    pub(crate) unknown: RefCell<Vec<EnvDeserializationError>>,
+    /// Paths whose values failed to deserialize in an earlier pass, see
+    /// [`Config::accumulate_errors`]
+    pub(crate) skipped: RefCell<Vec<Vec<String>>>,
+    /// Simple values given to `deserialize_any`, see [`BufferedValue`]
+    pub(crate) buffered: RefCell<Vec<BufferedValue>>,
+}
+
+/// A simple value given to `deserialize_any`.
+///
+/// serde buffers these values for e.g. internally tagged enums and flattened fields, and only
+/// deserializes them once this deserializer returned. Errors about them then carry neither the
+/// variable nor the path, which is why the values are kept to find the one an error is about.
+#[derive(Debug)]
+pub(crate) struct BufferedValue {
+    key: String,
+    path: Vec<String>,
+    /// How serde describes the value in errors, e.g. ``integer `5` ``
+    unexpected: String,
+}
+
+impl Context {
+    /// Returns the name of the variable the given key was read from.
+    ///
+    /// For keys that point to nested values, the name is taken from one of the variables nested
+    /// below it, which keeps the casing the user wrote.
+    fn variable_name(&self, config: &Config<'_>, key: &str) -> String {
+        if let Some(name) = self.origins.get(key) {
+            return name.clone();
+        }
+
+        let nested_prefix = format!("{}{}", key, config.separator());
+        self.origins
+            .range::<str, _>((Bound::Excluded(nested_prefix.as_str()), Bound::Unbounded))
+            .next()
+            .filter(|(nested_key, _)| nested_key.starts_with(&nested_prefix))
+            // The parts of the key may have been decoded, so the parts of the name nested below it
+            // are removed by count
+            .and_then(|(nested_key, name)| {
+                let nested = nested_key[key.len()..].matches(config.separator()).count();
+                name.rsplitn(nested + 1, config.separator())
+                    .last()
+                    .map(str::to_owned)
+            })
+            .or_else(|| {
+                // Values can also be read from a variable further up, e.g. when given as JSON
+                let mut ancestor = key;
+                while let Some((parent, _)) = ancestor.rsplit_once(config.separator()) {
+                    if let Some(name) = self.origins.get(parent) {
+                        return Some(name.clone());
+                    }
+                    ancestor = parent;
+                }
+                None
+            })
+            .unwrap_or_else(|| config.variable_name(key))
+    }
+
+    /// Finds the key and path of the buffered value below the given path that the given error of
+    /// serde is about, if it is unambiguous, see [`BufferedValue`]
+    fn buffered_value(&self, path: &[String], message: &str) -> Option<(String, Vec<String>)> {
+        let is_about = |value: &BufferedValue| {
+            ["invalid type: ", "invalid value: "].iter().any(|prefix| {
+                matches!(
+                    message
+                        .strip_prefix(prefix)
+                        .and_then(|rest| rest.strip_prefix(&value.unexpected)),
+                    Some(rest) if rest.starts_with(", expected")
+                )
+            })
+        };
+
+        let mut values = self
+            .buffered
+            .borrow()
+            .iter()
+            .filter(|value| value.path.len() > path.len() && value.path.starts_with(path))
+            .filter(|value| is_about(value))
+            .map(|value| (value.key.clone(), value.path.clone()))
+            .collect::<Vec<_>>();
+        values.sort();
+        values.dedup();
+
+        match values.len() {
+            1 => values.pop(),
+            _ => None,
+        }
+    }
+
+    fn is_skipped(&self, path: &[String]) -> bool {
+        self.skipped.borrow().iter().any(|skipped| skipped == path)
+    }
+}
+
+#[derive(Clone)]
 pub(crate) struct Parser<'a> {
     pub(crate) config: &'a Config<'a>,
+    pub(crate) context: Rc<Context>,
+    /// The key of the current value in the tree, i.e. the path joined with the separator
+    pub(crate) key: String,
+    /// The path of fields, keys and variants leading to the current value
+    pub(crate) path: Vec<String>,
     pub(crate) current: Value,
 }
 
 impl Value {
+    /// Returns all simple values nested below this value, together with their path.
+    pub(crate) fn into_leaves(self) -> Vec<(Vec<String>, String)> {
+        match self {
+            Value::Simple(value) => vec![(vec![], value)],
+            Value::Map(values) => values
+                .into_iter()
+                .flat_map(|(key, value)| {
+                    value
+                        .into_leaves()
+                        .into_iter()
+                        .map(move |(mut path, value)| {
+                            path.insert(0, key.clone());
+                            (path, value)
+                        })
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the path of the simple value found along the given path, if any, i.e. the value
+    /// that a value inserted at the given path conflicts with, see [`Self::insert_at`]
+    pub(crate) fn simple_along(&self, path: &[&str]) -> Option<Vec<String>> {
+        let mut current = self;
+        for (depth, part) in path.iter().enumerate() {
+            match current {
+                Self::Simple(_) => {
+                    return Some(path[..depth].iter().map(|s| s.to_string()).collect())
+                }
+                Self::Map(values) => match values.iter().find(|(key, _)| key == part) {
+                    Some((_, value)) => current = value,
+                    None => return None,
+                },
+            }
+        }
+
+        match current {
+            Self::Simple(_) => Some(path.iter().map(|s| s.to_string()).collect()),
+            Self::Map(_) => None,
+        }
+    }
+
     pub(crate) fn insert_at(
         &mut self,
         path: &[&str],
@@ -67,26 +228,740 @@ impl Value {
     }
 }
 
+impl<'a> Parser<'a> {
+    /// Creates a parser for a value nested below the current one.
+    ///
+    /// `key` is the key of the value in the tree, while `name` is the name of the field, key or
+    /// variant it gets deserialized as.
+    fn child(&self, key: &str, name: String, value: Value) -> Self {
+        let mut path = self.path.clone();
+        path.push(name);
+
+        Self {
+            config: self.config,
+            context: self.context.clone(),
+            key: if self.key.is_empty() {
+                key.to_owned()
+            } else {
+                format!("{}{}{}", self.key, self.config.separator(), key)
+            },
+            path,
+            current: value,
+        }
+    }
+
+    /// Deserializes the given seed, or substitutes a [`Placeholder`] if the current value already
+    /// failed in an earlier pass.
+    fn deserialize_seed<T>(self, seed: T) -> Result<T::Value, EnvDeserializationError>
+    where
+        T: DeserializeSeed<'a>,
+    {
+        if self.context.is_skipped(&self.path) {
+            seed.deserialize(Placeholder)
+        } else {
+            // Scoping the whole seed also covers values that serde buffers and only deserializes
+            // after this deserializer returned, e.g. the fields of internally tagged enums
+            self.scoped(|parser| seed.deserialize(parser))
+        }
+    }
+
+    /// Deserializes a `T`, collecting as many errors as possible instead of stopping at the first.
+    ///
+    /// Every time deserialization fails, the path of the failing value is remembered and
+    /// deserialization is started over, with a [`Placeholder`] in place of every value that
+    /// failed before. Errors of values that contain a placeholder are not reported, as they are
+    /// most likely caused by it, but the values are skipped in the next pass as well. This stops
+    /// once deserialization succeeds, or the failing value cannot be skipped any further.
+    pub(crate) fn deserialize_accumulating<T>(self) -> Result<T, EnvDeserializationError>
+    where
+        T: DeserializeOwned,
+    {
+        let context = self.context.clone();
+
+        loop {
+            context.unknown.borrow_mut().clear();
+            context.buffered.borrow_mut().clear();
+
+            let result = T::deserialize(self.clone());
+
+            let error = match result {
+                Ok(value) => {
+                    let mut errors = context.errors.borrow_mut();
+                    errors.append(&mut context.unknown.borrow_mut());
+                    if errors.is_empty() {
+                        return Ok(value);
+                    }
+                    break;
+                }
+                Err(error) => error,
+            };
+
+            let path = match &error {
+                EnvDeserializationError::InVariable { path, .. }
+                | EnvDeserializationError::MissingVariable { path, .. } => path.clone(),
+                _ => vec![],
+            };
+
+            let mut skipped = context.skipped.borrow_mut();
+            let caused_by_placeholder = skipped.iter().any(|skipped| skipped.starts_with(&path));
+            let is_new = skipped.contains(&path).not();
+
+            if caused_by_placeholder.not() {
+                context.errors.borrow_mut().push(error);
+            }
+
+            if path.is_empty() || is_new.not() {
+                context
+                    .errors
+                    .borrow_mut()
+                    .append(&mut context.unknown.borrow_mut());
+                break;
+            }
+
+            skipped.push(path);
+        }
+
+        let errors = context.errors.take();
+        Err(EnvDeserializationError::Multiple(errors))
+    }
+
+    /// Parses the keys of the given values as array indices, see [`Config::dense_arrays`].
+    ///
+    /// The values are returned sorted by their index. Values that failed in an earlier pass are
+    /// left out, as they will be replaced by a placeholder anyway.
+    fn indexed(
+        &self,
+        values: Vec<(String, Value)>,
+    ) -> Result<Vec<(usize, String, Value)>, EnvDeserializationError> {
+        let mut indexed: Vec<(usize, String, Value)> = Vec::with_capacity(values.len());
+
+        for (key, value) in values {
+            let child = self.child(&key, key.clone(), value);
+            if self.context.is_skipped(&child.path) {
+                continue;
+            }
+
+            let index = match key.parse::<usize>() {
+                Ok(index) if key.bytes().all(|byte| byte.is_ascii_digit()) => index,
+                _ => return child.scoped(|_| Err(EnvDeserializationError::InvalidArrayIndex(key))),
+            };
+
+            if indexed.iter().any(|(other, ..)| *other == index) {
+                return child.scoped(|_| Err(EnvDeserializationError::DuplicateArrayIndex(index)));
+            }
+
+            indexed.push((index, key, child.current));
+        }
+
+        indexed.sort_by_key(|(index, ..)| *index);
+        Ok(indexed)
+    }
+
+    /// Creates the element for the given index of an array or tuple, if it is missing.
+    ///
+    /// This is only possible if the element failed in an earlier pass, in which case it gets
+    /// replaced by a placeholder. Otherwise it is reported as missing.
+    fn missing_element(&self, index: usize) -> Result<Self, EnvDeserializationError> {
+        let key = index.to_string();
+        let child = self.child(&key, key.clone(), Value::Map(vec![]));
+
+        if self.context.is_skipped(&child.path) {
+            Ok(child)
+        } else {
+            Err(EnvDeserializationError::MissingField(key))
+        }
+    }
+
+    /// Visits the given simple value as a sequence, see [`Config::with_list_delimiter`]
+    ///
+    /// Without a delimiter, the value is the only element of the sequence.
+    fn visit_list<V>(self, value: String, visitor: V) -> Result<V::Value, EnvDeserializationError>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        match self.config.list_delimiter(&self.path) {
+            Some(delimiter) => {
+                Entries::new(self.list_elements(&value, delimiter)).visit_seq(visitor)
+            }
+            None => {
+                let current = Value::Simple(value);
+                Entries::new(vec![(String::new(), Self { current, ..self })]).visit_seq(visitor)
+            }
+        }
+    }
+
+    /// Splits the given value into the elements of a sequence, see [`Config::with_list_delimiter`]
+    ///
+    /// The elements are read from the same variable as the whole sequence.
+    fn list_elements(&self, value: &str, delimiter: &str) -> Vec<(String, Self)> {
+        let value = if self.config.trim_list_elements {
+            value.trim()
+        } else {
+            value
+        };
+
+        if value.is_empty() {
+            return vec![];
+        }
+
+        split_escaped(value, delimiter)
+            .into_iter()
+            .enumerate()
+            .map(|(index, element)| {
+                let element = if self.config.trim_list_elements {
+                    element.trim().to_owned()
+                } else {
+                    element
+                };
+
+                let mut path = self.path.clone();
+                path.push(index.to_string());
+
+                let element = Self {
+                    config: self.config,
+                    context: self.context.clone(),
+                    key: self.key.clone(),
+                    path,
+                    current: Value::Simple(element),
+                };
+                (String::new(), element)
+            })
+            .collect()
+    }
+
+    /// Returns the given value as the type it looks like, see [`Config::with_type_inference`]
+    fn infer(&self, value: String, types: &[InferredType]) -> Inferred {
+        if self.config.is_null(&value) {
+            return Inferred::Unit;
+        }
+
+        for inferred in types {
+            match inferred {
+                InferredType::Bool => {
+                    if let Ok(value) = value.parse::<bool>() {
+                        return Inferred::Bool(value);
+                    }
+                }
+                InferredType::Integer => {
+                    if let Ok(value) = value.parse::<u64>() {
+                        return Inferred::Unsigned(value);
+                    }
+                    if let Ok(value) = value.parse::<i64>() {
+                        return Inferred::Signed(value);
+                    }
+                }
+                InferredType::Float => match value.parse::<f64>() {
+                    // Rust also parses e.g. `inf` and `NaN`, which are more likely meant as strings
+                    Ok(float) if value.bytes().any(|byte| byte.is_ascii_digit()) => {
+                        return Inferred::Float(float);
+                    }
+                    _ => {}
+                },
+                InferredType::String => break,
+            }
+        }
+
+        Inferred::String(value)
+    }
+
+    /// Reports all variables making up the current value as unknown, see [`Config::strict`]
+    fn report_unknown(&self, suggestion: Option<&str>) {
+        match &self.current {
+            Value::Simple(_) => {
+                self.context
+                    .unknown
+                    .borrow_mut()
+                    .push(EnvDeserializationError::UnknownVariable {
+                        variable: self.context.variable_name(self.config, &self.key),
+                        path: self.path.clone(),
+                        suggestion: suggestion.map(str::to_owned),
+                    })
+            }
+            Value::Map(values) => {
+                for (key, value) in values {
+                    self.child(key, key.clone(), value.clone())
+                        .report_unknown(suggestion);
+                }
+            }
+        }
+    }
+
+    /// Replaces the current value with the value it contains as JSON, if any, see
+    /// `Config::json_values`
+    fn expand_json(self) -> Result<Self, EnvDeserializationError> {
+        let current = match &self.current {
+            Value::Simple(val) => match self.config.json_value(val) {
+                Some(current) => current?,
+                None => return Ok(self),
+            },
+            Value::Map(_) => return Ok(self),
+        };
+
+        Ok(Self { current, ..self })
+    }
+
+    /// Prepares the current value to be read into the given fields or variants, which are empty
+    /// for maps and sequences, see [`Self::resolve_keys`] and [`Self::expand_json`]
+    fn prepare(self, names: &[&str]) -> Result<Self, EnvDeserializationError> {
+        self.resolve_keys(names)?.expand_json()
+    }
+
+    /// Groups the values of the current map by the field, variant or key their keys start with,
+    /// see [`Config::schema_guided`]
+    ///
+    /// The given names are the fields or variants the values can be read into, which are empty
+    /// for maps and sequences.
+    fn resolve_keys(self, names: &[&str]) -> Result<Self, EnvDeserializationError> {
+        let (values, parser) = match self.into_parts() {
+            (Value::Map(values), parser) if parser.config.schema_guided => (values, parser),
+            (current, parser) => return Ok(Self { current, ..parser }),
+        };
+
+        let mut groups: Vec<(String, Vec<_>)> = vec![];
+        for (key, value) in values {
+            let (head, rest) = parser.config.split_key(&key, names);
+            let entry = (rest.map(str::to_owned), value);
+
+            match groups.iter_mut().find(|(other, _)| other == head) {
+                Some((_, entries)) => entries.push(entry),
+                None => groups.push((head.to_owned(), vec![entry])),
+            }
+        }
+
+        let mut resolved = Vec::with_capacity(groups.len());
+        for (head, mut entries) in groups {
+            let value = match entries.iter().position(|(rest, _)| rest.is_none()) {
+                Some(_) if entries.len() > 1 => {
+                    let child = parser.child(&head, head.clone(), Value::Map(vec![]));
+                    let path = child.path.clone();
+                    return child.scoped(|_| Err(EnvDeserializationError::InvalidEnvNesting(path)));
+                }
+                Some(position) => entries.remove(position).1,
+                None => Value::Map(
+                    entries
+                        .into_iter()
+                        .map(|(rest, value)| (rest.unwrap_or_default(), value))
+                        .collect(),
+                ),
+            };
+            resolved.push((head, value));
+        }
+
+        Ok(Self {
+            current: Value::Map(resolved),
+            ..parser
+        })
+    }
+
+    /// Separates the current value from the parser, so that children can be created from it.
+    fn into_parts(mut self) -> (Value, Self) {
+        let current = std::mem::replace(&mut self.current, Value::Map(vec![]));
+        (current, self)
+    }
+
+    /// Runs the given deserialization, attaching the name of the current variable and its path to
+    /// any error that does not carry one yet, see [`Self::scope_error`].
+    fn scoped<T, F>(self, deserialize: F) -> Result<T, EnvDeserializationError>
+    where
+        F: FnOnce(Self) -> Result<T, EnvDeserializationError>,
+    {
+        let scope = Self {
+            config: self.config,
+            context: self.context.clone(),
+            key: self.key.clone(),
+            path: self.path.clone(),
+            current: Value::Map(vec![]),
+        };
+
+        deserialize(self).map_err(|error| scope.scope_error(error))
+    }
+
+    /// Attaches the name of the current variable and its path to the given error, unless it
+    /// already carries one.
+    ///
+    /// Missing fields are reported as the variable that would have to be set.
+    fn scope_error(&self, error: EnvDeserializationError) -> EnvDeserializationError {
+        // Errors about buffered values only surface here, after the values were deserialized
+        if let EnvDeserializationError::GenericDeserialization(message) = &error {
+            if let Some((key, path)) = self.context.buffered_value(&self.path, message) {
+                return EnvDeserializationError::InVariable {
+                    variable: self.context.variable_name(self.config, &key),
+                    path,
+                    source: Box::new(error),
+                };
+            }
+        }
+
+        let mut path = self.path.clone();
+
+        match error {
+            error @ (EnvDeserializationError::InVariable { .. }
+            | EnvDeserializationError::MissingVariable { .. }) => error,
+            EnvDeserializationError::MissingField(field) => {
+                let key = if self.key.is_empty() {
+                    field.clone()
+                } else {
+                    format!("{}{}{}", self.key, self.config.separator(), field)
+                };
+                path.push(field);
+
+                EnvDeserializationError::MissingVariable {
+                    variable: self.config.variable_name(&key),
+                    path,
+                }
+            }
+            error if path.is_empty() => error,
+            error => EnvDeserializationError::InVariable {
+                variable: self.context.variable_name(self.config, &self.key),
+                path,
+                source: Box::new(error),
+            },
+        }
+    }
+}
+
 macro_rules! forward_to_deserializer {
     ($($ty:ident => $method:ident),* $(,)?) => {
         $(
             fn $method<V>(self, visitor: V) -> Result<V::Value, Self::Error>
                 where V: serde::de::Visitor<'de>
             {
-                match self.current {
+                self.scoped(|parser| match parser.current {
                     Value::Simple(val) => {
-                        match val.parse::<$ty>() {
-                            Ok(val) => val.into_deserializer().$method(visitor),
-                            Err(e) => Err(crate::error::EnvDeserializationError::GenericDeserialization(format!("'{}' could not be deserialized due to: {}", val, e))),
-                        }
+                        <$ty as ParseValue>::parse_value(&val, parser.config, &parser.path)?
+                            .into_deserializer()
+                            .$method(visitor)
                     }
                     Value::Map(_) => Err(crate::error::EnvDeserializationError::InvalidNestedValues)
-                }
+                })
             }
         )*
     };
 }
 
+/// A simple value as it is given to `deserialize_any`, see [`Config::with_type_inference`]
+enum Inferred {
+    Unit,
+    Bool(bool),
+    Unsigned(u64),
+    Signed(i64),
+    Float(f64),
+    String(String),
+}
+
+impl Inferred {
+    /// Describes the value the way serde does in errors
+    fn unexpected(&self) -> Unexpected<'_> {
+        match self {
+            Self::Unit => Unexpected::Unit,
+            Self::Bool(value) => Unexpected::Bool(*value),
+            Self::Unsigned(value) => Unexpected::Unsigned(*value),
+            Self::Signed(value) => Unexpected::Signed(*value),
+            Self::Float(value) => Unexpected::Float(*value),
+            Self::String(value) => Unexpected::Str(value),
+        }
+    }
+
+    fn visit<'de, V>(self, visitor: V) -> Result<V::Value, EnvDeserializationError>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        match self {
+            Self::Unit => visitor.visit_unit(),
+            Self::Bool(value) => visitor.visit_bool(value),
+            Self::Unsigned(value) => visitor.visit_u64(value),
+            Self::Signed(value) => visitor.visit_i64(value),
+            Self::Float(value) => visitor.visit_f64(value),
+            Self::String(value) => visitor.visit_string(value),
+        }
+    }
+}
+
+/// Splits the given value at each delimiter that is not escaped with `\`
+fn split_escaped(value: &str, delimiter: &str) -> Vec<String> {
+    let mut elements = vec![];
+    let mut element = String::new();
+    let mut rest = value;
+
+    while let Some(c) = rest.chars().next() {
+        if let Some(escaped) = rest.strip_prefix('\\') {
+            if let Some(remaining) = escaped.strip_prefix(delimiter) {
+                element.push_str(delimiter);
+                rest = remaining;
+                continue;
+            }
+            if let Some(remaining) = escaped.strip_prefix('\\') {
+                element.push('\\');
+                rest = remaining;
+                continue;
+            }
+        } else if let Some(remaining) = rest.strip_prefix(delimiter) {
+            elements.push(std::mem::take(&mut element));
+            rest = remaining;
+            continue;
+        }
+
+        element.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    elements.push(element);
+    elements
+}
+
+/// Splits the given value into the keys and values of a map, see [`Config::with_inline_maps`]
+///
+/// Separators can be escaped with `\`, as can `\` itself.
+pub(crate) fn split_inline_map(
+    value: &str,
+    pair_separator: &str,
+    key_value_separator: &str,
+    trim: bool,
+) -> Result<Vec<(String, String)>, EnvDeserializationError> {
+    let value = if trim { value.trim() } else { value };
+    if value.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let entry = |key: Option<String>, value: String| match key {
+        Some(key) if trim => Ok((key.trim().to_owned(), value.trim().to_owned())),
+        Some(key) => Ok((key, value)),
+        None => Err(EnvDeserializationError::InvalidMapEntry(value)),
+    };
+
+    let mut entries = vec![];
+    let mut key = None;
+    let mut current = String::new();
+    let mut rest = value;
+
+    while let Some(c) = rest.chars().next() {
+        if let Some(escaped) = rest.strip_prefix('\\') {
+            let separator = [pair_separator, key_value_separator, "\\"]
+                .into_iter()
+                .find(|separator| escaped.starts_with(separator));
+            if let Some(separator) = separator {
+                current.push_str(separator);
+                rest = &escaped[separator.len()..];
+                continue;
+            }
+        } else if let Some(remaining) = rest.strip_prefix(pair_separator) {
+            entries.push(entry(key.take(), std::mem::take(&mut current))?);
+            rest = remaining;
+            continue;
+        } else if key.is_none() {
+            if let Some(remaining) = rest.strip_prefix(key_value_separator) {
+                key = Some(std::mem::take(&mut current));
+                rest = remaining;
+                continue;
+            }
+        }
+
+        current.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    entries.push(entry(key, current)?);
+    Ok(entries)
+}
+
+/// Finds the name closest to the given (unknown) one, if any is close enough to be a likely typo.
+pub(crate) fn suggest<'n>(unknown: &str, names: &[&'n str]) -> Option<&'n str> {
+    let unknown = unknown.to_ascii_lowercase();
+
+    names
+        .iter()
+        .map(|name| (edit_distance(&unknown, &name.to_ascii_lowercase()), *name))
+        .filter(|(distance, name)| *distance <= (name.chars().count() / 3).max(1))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name)
+}
+
+/// The Levenshtein distance between the two given strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Gives serde access to the entries of a [`Value::Map`]
+struct Entries<'a> {
+    entries: std::vec::IntoIter<(String, Parser<'a>)>,
+    value: Option<Parser<'a>>,
+    consumed: usize,
+}
+
+impl<'a> Entries<'a> {
+    fn new(entries: Vec<(String, Parser<'a>)>) -> Self {
+        Self {
+            entries: entries.into_iter(),
+            value: None,
+            consumed: 0,
+        }
+    }
+
+    /// Visits the entries as a sequence of their values, making sure all of them are used.
+    fn visit_seq<V>(mut self, visitor: V) -> Result<V::Value, EnvDeserializationError>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        let value = visitor.visit_seq(&mut self)?;
+        self.end()?;
+        Ok(value)
+    }
+
+    /// Visits the entries as a map, making sure all of them are used.
+    fn visit_map<V>(mut self, visitor: V) -> Result<V::Value, EnvDeserializationError>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        let value = visitor.visit_map(&mut self)?;
+        self.end()?;
+        Ok(value)
+    }
+
+    fn end(self) -> Result<(), EnvDeserializationError> {
+        let remaining = self.entries.len();
+        if remaining == 0 {
+            Ok(())
+        } else {
+            Err(serde::de::Error::invalid_length(
+                self.consumed + remaining,
+                &format!("{} elements", self.consumed).as_str(),
+            ))
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for Entries<'de> {
+    type Error = EnvDeserializationError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.consumed += 1;
+                let result = seed
+                    .deserialize(Key::new(value.config, key))
+                    .map_err(|error| value.scope_error(error));
+                self.value = Some(value);
+                result.map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.value
+            .take()
+            .expect("next_value_seed is called after next_key_seed")
+            .deserialize_seed(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+impl<'de> SeqAccess<'de> for Entries<'de> {
+    type Error = EnvDeserializationError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.entries.next() {
+            Some((_key, value)) => {
+                self.consumed += 1;
+                value.deserialize_seed(seed).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+impl<'de> EnumAccess<'de> for Entries<'de> {
+    type Error = EnvDeserializationError;
+    type Variant = Parser<'de>;
+
+    fn variant_seed<V>(mut self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        match self.next_key_seed(seed)? {
+            Some(variant) => Ok((
+                variant,
+                self.value.expect("next_key_seed sets the value of a key"),
+            )),
+            None => Err(serde::de::Error::invalid_type(
+                serde::de::Unexpected::Map,
+                &"enum",
+            )),
+        }
+    }
+}
+
+impl<'de> VariantAccess<'de> for Parser<'de> {
+    type Error = EnvDeserializationError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        self.deserialize_seed(std::marker::PhantomData::<()>)
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.deserialize_seed(seed)
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        if self.context.is_skipped(&self.path) {
+            Placeholder.deserialize_tuple(len, visitor)
+        } else {
+            self.deserialize_tuple(len, visitor)
+        }
+    }
+
+    // The fields are given to `deserialize_struct`, so that they are known e.g. for coercing
+    // their case
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        if self.context.is_skipped(&self.path) {
+            Placeholder.deserialize_struct("", fields, visitor)
+        } else {
+            self.deserialize_struct("", fields, visitor)
+        }
+    }
+}
+
 impl<'de> IntoDeserializer<'de, EnvDeserializationError> for Parser<'de> {
     type Deserializer = Self;
 
@@ -102,28 +977,109 @@ impl<'de> Deserializer<'de> for Parser<'de> {
     where
         V: serde::de::Visitor<'de>,
     {
-        match self.current {
-            Value::Simple(val) => val.into_deserializer().deserialize_any(visitor),
-            Value::Map(_) => self.deserialize_map(visitor),
-        }
+        self.scoped(|parser| match parser.into_parts() {
+            (Value::Simple(val), parser) => {
+                let value = match parser.config.inferred_types() {
+                    Some(types) => parser.infer(val, types),
+                    None => Inferred::String(val),
+                };
+
+                parser.context.buffered.borrow_mut().push(BufferedValue {
+                    key: parser.key.clone(),
+                    path: parser.path.clone(),
+                    unexpected: value.unexpected().to_string(),
+                });
+
+                value.visit(visitor)
+            }
+            (current, parser) => Self { current, ..parser }.deserialize_map(visitor),
+        })
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.scoped(|parser| match parser.current {
+            Value::Simple(val) => visitor.visit_string(val),
+            Value::Map(_) => Err(EnvDeserializationError::InvalidNestedValues),
+        })
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.scoped(
+            |parser| match (parser.config.binary_encoding, parser.into_parts()) {
+                (Some(encoding), (Value::Simple(val), _)) => {
+                    visitor.visit_byte_buf(encoding.decode(&val)?)
+                }
+                (None, (Value::Simple(val), _)) => visitor.visit_string(val),
+                (_, (current, parser)) => Self { current, ..parser }.deserialize_any(visitor),
+            },
+        )
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
     }
 
     fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        match self.current {
-            Value::Simple(_) => {
-                SeqDeserializer::new(std::iter::once(self)).deserialize_seq(visitor)
+        self.scoped(|parser| match parser.prepare(&[])?.into_parts() {
+            (Value::Simple(val), parser) => parser.visit_list(val, visitor),
+            (Value::Map(values), parser) if parser.config.dense_arrays => {
+                let mut elements = Vec::with_capacity(values.len());
+                for (index, key, value) in parser.indexed(values)? {
+                    // Only elements that failed in an earlier pass can be filled in, so this
+                    // stops at the first real gap no matter how large the given index is
+                    while elements.len() < index {
+                        let element = parser.missing_element(elements.len())?;
+                        elements.push((String::new(), element));
+                    }
+                    elements.push((String::new(), parser.child(&key, key.clone(), value)));
+                }
+
+                Entries::new(elements).visit_seq(visitor)
             }
-            Value::Map(values) if self.config.ordered_arrays.not() => {
-                let values = values.into_iter().map(|(_key, val)| Self {
-                    current: val,
-                    config: self.config,
-                });
-                SeqDeserializer::new(values).deserialize_seq(visitor)
+            (Value::Map(values), parser) if parser.config.ordered_arrays.not() => {
+                let values = values
+                    .into_iter()
+                    .map(|(key, val)| {
+                        let child = parser.child(&key, key.clone(), val);
+                        (key, child)
+                    })
+                    .collect();
+                Entries::new(values).visit_seq(visitor)
             }
-            Value::Map(values) => {
+            (Value::Map(values), parser) => {
                 // Convert the key into a two part sorting token:
                 // 1. An optional numeric prefix
                 // 2. A (potentially empty) string suffix
@@ -143,29 +1099,107 @@ impl<'de> Deserializer<'de> for Parser<'de> {
 
                         let rest = chars.collect::<String>();
 
-                        (
-                            (num, rest),
-                            Parser {
-                                current: value,
-                                config: self.config,
-                            },
-                        )
+                        let child = parser.child(&key, key.clone(), value);
+
+                        ((num, rest), (key, child))
                     })
                     .collect();
 
                 values.sort_by(|(key1, _value1), (key2, _value2)| key1.cmp(key2));
 
-                SeqDeserializer::new(values.into_iter().map(|(_key, value)| value))
-                    .deserialize_seq(visitor)
+                Entries::new(values.into_iter().map(|(_key, value)| value).collect())
+                    .visit_seq(visitor)
             }
-        }
+        })
     }
 
     fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        visitor.visit_some(self)
+        match &self.current {
+            Value::Simple(val) if self.config.is_null(val) => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        match &self.current {
+            Value::Simple(val) if self.config.is_null(val) => visitor.visit_unit(),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.scoped(|parser| match parser.prepare(&[])?.into_parts() {
+            (Value::Simple(val), parser) => parser.visit_list(val, visitor),
+            (Value::Map(values), parser) => {
+                let mut values: Vec<_> = if parser.config.dense_arrays {
+                    parser
+                        .indexed(values)?
+                        .into_iter()
+                        .map(|(index, key, value)| (Some(index), key, value))
+                        .collect()
+                } else {
+                    values
+                        .into_iter()
+                        .map(|(key, value)| (key.parse::<usize>().ok(), key, value))
+                        .collect()
+                };
+
+                let mut elements = Vec::with_capacity(len);
+                for index in 0..len {
+                    let element = match values.iter().position(|(i, ..)| *i == Some(index)) {
+                        Some(position) => {
+                            let (_, key, value) = values.remove(position);
+                            parser.child(&key, key.clone(), value)
+                        }
+                        None => parser.missing_element(index)?,
+                    };
+                    elements.push((String::new(), element));
+                }
+
+                // Any remaining values are beyond the length of the tuple
+                let given = len + values.len();
+                if let Some((_, key, value)) = values.into_iter().next() {
+                    let error = serde::de::Error::invalid_length(given, &visitor);
+                    return parser
+                        .child(&key, key.clone(), value)
+                        .scoped(|_| Err(error));
+                }
+
+                Entries::new(elements).visit_seq(visitor)
+            }
+        })
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.deserialize_tuple(len, visitor)
     }
 
     fn deserialize_newtype_struct<V>(
@@ -188,69 +1222,149 @@ impl<'de> Deserializer<'de> for Parser<'de> {
     where
         V: serde::de::Visitor<'de>,
     {
-        match self.current {
-            Value::Simple(val) => visitor.visit_enum(val.into_deserializer()),
-            Value::Map(values) => {
+        self.scoped(|parser| match parser.prepare(variants)?.into_parts() {
+            (Value::Simple(val), _) => visitor.visit_enum(val.into_deserializer()),
+            (Value::Map(values), parser) => {
                 // Coerce variants into correct casing if requested
-                let values = self.config.maybe_coerce_case(values, variants);
-
-                visitor.visit_enum(MapAccessDeserializer::new(MapDeserializer::new(
-                    values.map(|(k, v)| {
-                        (
-                            k,
-                            Self {
-                                current: v,
-                                config: self.config,
-                            },
-                        )
-                    }),
-                )))
+                let values = parser.config.maybe_coerce_case(
+                    values
+                        .into_iter()
+                        .map(|(key, value)| (key.clone(), (key, value))),
+                    variants,
+                );
+
+                let values: Vec<_> = values
+                    .map(|(name, (key, value))| {
+                        let child = parser.child(&key, name.clone(), value);
+                        (name, child)
+                    })
+                    .collect();
+
+                // Only the first variant is used, any others would be silently ignored
+                if parser.config.strict {
+                    for (name, child) in values.iter().skip(1) {
+                        child.report_unknown(suggest(name, variants));
+                    }
+                }
+
+                visitor.visit_enum(Entries::new(values))
             }
-        }
+        })
     }
 
     fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        match self.current {
-            Value::Simple(_) => Err(EnvDeserializationError::UnsupportedValue),
-            Value::Map(values) => {
-                visitor.visit_map(MapDeserializer::new(values.into_iter().map(|(k, v)| {
-                    (
-                        k,
-                        Self {
-                            current: v,
-                            config: self.config,
-                        },
-                    )
-                })))
-            }
-        }
+        self.scoped(|parser| match parser.prepare(&[])?.into_parts() {
+            (Value::Simple(val), parser) => match parser.config.inline_map_entries(&val) {
+                Some(entries) => {
+                    let entries = entries?
+                        .into_iter()
+                        .map(|(key, value)| {
+                            // The entries are read from the same variable as the whole map
+                            let mut path = parser.path.clone();
+                            path.push(key.clone());
+
+                            let entry = Self {
+                                path,
+                                key: parser.key.clone(),
+                                context: parser.context.clone(),
+                                current: Value::Simple(value),
+                                ..parser
+                            };
+                            (key, entry)
+                        })
+                        .collect();
+
+                    Entries::new(entries).visit_map(visitor)
+                }
+                None => Err(EnvDeserializationError::UnsupportedValue),
+            },
+            (Value::Map(values), parser) => Entries::new(
+                values
+                    .into_iter()
+                    .map(|(key, value)| {
+                        let child = parser.child(&key, key.clone(), value);
+                        (key, child)
+                    })
+                    .collect(),
+            )
+            .visit_map(visitor),
+        })
     }
 
     fn deserialize_struct<V>(
         self,
-        _name: &'static str,
+        name: &'static str,
         fields: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        let parser = match self.current {
-            Value::Simple(_) => self,
-            Value::Map(values) => {
+        self.scoped(|parser| match parser.prepare(fields)?.into_parts() {
+            (Value::Simple(val), _) if name == duration::NAME && fields == duration::FIELDS => {
+                let duration = duration::parse(&val)?;
+                let parts = [duration.as_secs(), u64::from(duration.subsec_nanos())];
+                visitor.visit_seq(SeqDeserializer::new(parts.into_iter()))
+            }
+            (Value::Simple(val), _) if name == timestamp::NAME && fields == timestamp::FIELDS => {
+                let since_epoch = timestamp::parse(&val)?;
+                let parts = [since_epoch.as_secs(), u64::from(since_epoch.subsec_nanos())];
+                visitor.visit_seq(SeqDeserializer::new(parts.into_iter()))
+            }
+            (Value::Simple(_), _) => Err(EnvDeserializationError::UnsupportedValue),
+            (Value::Map(values), parser) => {
                 // Coerce variants into correct casing if requested
-                let values = self.config.maybe_coerce_case(values, fields);
-                Self {
-                    config: self.config,
-                    current: Value::Map(values.collect()),
+                let values = parser.config.maybe_coerce_case(
+                    values
+                        .into_iter()
+                        .map(|(key, value)| (key.clone(), (key, value))),
+                    fields,
+                );
+
+                let mut values: Vec<_> = values
+                    .map(|(name, (key, value))| {
+                        let child = parser.child(&key, name.clone(), value);
+                        (name, child)
+                    })
+                    .collect();
+
+                // Give the fields in the order they are declared in, so that e.g. the tag of an
+                // adjacently tagged enum comes before its content and the content does not need
+                // to be buffered
+                values.sort_by_key(|(name, _)| {
+                    fields
+                        .iter()
+                        .position(|field| field == name)
+                        .unwrap_or(fields.len())
+                });
+
+                if parser.config.strict {
+                    for (name, child) in &values {
+                        if fields.contains(&name.as_str()).not() {
+                            child.report_unknown(suggest(name, fields));
+                        }
+                    }
+                }
+
+                // Fields that failed in an earlier pass need a placeholder, even if they are
+                // missing entirely
+                for field in fields {
+                    if values.iter().any(|(name, _)| name == field) {
+                        continue;
+                    }
+
+                    let child = parser.child(field, field.to_string(), Value::Map(vec![]));
+                    if parser.context.is_skipped(&child.path) {
+                        values.push((field.to_string(), child));
+                    }
                 }
-            }
-        };
 
-        parser.deserialize_map(visitor)
+                Entries::new(values).visit_map(visitor)
+            }
+        })
     }
 
     forward_to_deserializer! {
@@ -262,14 +1376,15 @@ impl<'de> Deserializer<'de> for Parser<'de> {
         i32 => deserialize_i32,
         u64 => deserialize_u64,
         i64 => deserialize_i64,
+        u128 => deserialize_u128,
+        i128 => deserialize_i128,
         f32 => deserialize_f32,
         f64 => deserialize_f64,
         bool => deserialize_bool,
     }
 
     serde::forward_to_deserialize_any! {
-        char str string bytes byte_buf unit unit_struct tuple_struct
-        identifier tuple ignored_any
+        ignored_any
     }
 }
 
@@ -291,10 +1406,7 @@ mod tests {
 
     impl Parser<'static> {
         fn simple(s: impl Into<String>) -> Self {
-            Self {
-                config: &CONFIG,
-                current: Value::simple(s),
-            }
+            Self::from(Value::simple(s))
         }
     }
 
@@ -302,6 +1414,9 @@ mod tests {
         fn from(value: Value) -> Self {
             Self {
                 config: &CONFIG,
+                context: Default::default(),
+                key: String::new(),
+                path: vec![],
                 current: value,
             }
         }
@@ -0,0 +1,108 @@
+#![allow(dead_code)]
+use envious::EnvDeserializationError;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+struct Inner {
+    enabled: bool,
+    name: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct Config {
+    port: u16,
+    host: String,
+    inner: Inner,
+    sizes: Vec<u8>,
+    retries: Option<u32>,
+    flag: bool,
+}
+
+fn variables(error: EnvDeserializationError) -> Vec<String> {
+    match error {
+        EnvDeserializationError::Multiple(errors) => errors
+            .into_iter()
+            .map(|error| match error {
+                EnvDeserializationError::InVariable { variable, .. }
+                | EnvDeserializationError::MissingVariable { variable, .. } => variable,
+                error => panic!("Unexpected error: {:?}", error),
+            })
+            .collect(),
+        error => panic!("Unexpected error: {:?}", error),
+    }
+}
+
+#[test]
+fn collects_all_errors() {
+    let vars = [
+        ("APP_PORT", "eighty"),
+        ("APP_INNER__ENABLED", "maybe"),
+        ("APP_SIZES__0", "1"),
+        ("APP_SIZES__1", "300"),
+        ("APP_RETRIES", "-1"),
+        ("APP_FLAG", "true"),
+        ("APP_FLAG__EXTRA", "true"),
+    ];
+
+    let error = envious::Config::new()
+        .with_prefix("APP_")
+        .accumulate_errors(true)
+        .build_from_iter::<Config, _, _, _>(vars)
+        .unwrap_err();
+
+    let mut variables = variables(error);
+    variables.sort();
+
+    assert_eq!(
+        variables,
+        [
+            "APP_FLAG__EXTRA",
+            "APP_HOST",
+            "APP_INNER__ENABLED",
+            "APP_INNER__NAME",
+            "APP_PORT",
+            "APP_RETRIES",
+            "APP_SIZES__1",
+        ]
+    );
+}
+
+#[test]
+fn reports_nesting_conflicts_once() {
+    let vars = [
+        ("APP_PORT", "80"),
+        ("APP_HOST", "localhost"),
+        ("APP_INNER", "enabled"),
+        ("APP_INNER__ENABLED", "true"),
+        ("APP_SIZES__0", "8"),
+        ("APP_FLAG", "false"),
+    ];
+
+    let error = envious::Config::new()
+        .with_prefix("APP_")
+        .accumulate_errors(true)
+        .build_from_iter::<Config, _, _, _>(vars)
+        .unwrap_err();
+
+    assert_eq!(variables(error), ["APP_INNER__ENABLED"]);
+}
+
+#[test]
+fn succeeds_without_errors() {
+    let vars = [
+        ("port", "80"),
+        ("host", "localhost"),
+        ("inner__enabled", "true"),
+        ("inner__name", "foo"),
+        ("sizes__0", "8"),
+        ("flag", "false"),
+    ];
+
+    let config: Config = envious::Config::new()
+        .accumulate_errors(true)
+        .build_from_iter(vars)
+        .unwrap();
+
+    assert_eq!(config.port, 80);
+    assert_eq!(config.sizes, [8]);
+}
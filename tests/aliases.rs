@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use envious::EnvDeserializationError;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Server {
+    port: u16,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Config {
+    server: Server,
+    database_url: String,
+}
+
+#[test]
+fn read_aliased_variables() {
+    let config: Config = envious::Config::new()
+        .with_prefix("APP_")
+        .with_alias("PORT", "server__port")
+        .with_alias("DATABASE_URL", "database_url")
+        .build_from_iter([("PORT", "8080"), ("DATABASE_URL", "postgres://db/app")])
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            server: Server { port: 8080 },
+            database_url: String::from("postgres://db/app"),
+        }
+    );
+}
+
+#[test]
+fn canonical_variables_take_precedence() {
+    let config: Config = envious::Config::new()
+        .with_prefix("APP_")
+        .with_alias("PORT", "server__port")
+        .with_alias("DATABASE_URL", "database_url")
+        .build_from_iter([
+            ("PORT", "8080"),
+            ("APP_SERVER__PORT", "9090"),
+            ("DATABASE_URL", "postgres://db/app"),
+        ])
+        .unwrap();
+
+    assert_eq!(config.server.port, 9090);
+}
+
+#[test]
+fn first_alias_takes_precedence() {
+    let config: Config = envious::Config::new()
+        .with_alias("DATABASE_URL", "database_url")
+        .with_alias("POSTGRES_URL", "database_url")
+        .with_alias("PORT", "server__port")
+        .strict(true)
+        .build_from_iter([
+            ("POSTGRES_URL", "postgres://other/app"),
+            ("DATABASE_URL", "postgres://db/app"),
+            ("port", "8080"),
+        ])
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            server: Server { port: 8080 },
+            database_url: String::from("postgres://db/app"),
+        }
+    );
+}
+
+#[test]
+fn errors_name_the_alias() {
+    let error = envious::Config::new()
+        .with_prefix("APP_")
+        .with_alias("PORT", "server__port")
+        .build_from_iter::<Config, _, _, _>([
+            ("PORT", "http"),
+            ("APP_DATABASE_URL", "postgres://db/app"),
+        ])
+        .unwrap_err();
+
+    assert!(
+        matches!(
+            &error,
+            EnvDeserializationError::InVariable { variable, path, .. }
+                if variable == "PORT" && path == &["server", "port"]
+        ),
+        "{:?}",
+        error
+    );
+}
+
+#[test]
+fn aliases_respect_case_sensitivity() {
+    let error = envious::Config::new()
+        .case_sensitive(true)
+        .with_alias("PORT", "server__port")
+        .build_from_iter::<Config, _, _, _>([("port", "8080"), ("database_url", "postgres://db")])
+        .unwrap_err();
+
+    assert!(
+        matches!(&error, EnvDeserializationError::MissingVariable { .. }),
+        "{:?}",
+        error
+    );
+}
+
+#[test]
+fn aliases_for_nested_values() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Config {
+        labels: HashMap<String, String>,
+    }
+
+    let build = |vars: &[(&str, &str)]| {
+        envious::Config::new()
+            .with_prefix("APP_")
+            .with_alias("LABELS", "labels")
+            .with_inline_maps(",", "=")
+            .build_from_iter::<Config, _, _, _>(vars.iter().copied())
+            .unwrap()
+    };
+
+    let config = build(&[("LABELS", "team=core,tier=1")]);
+    assert_eq!(config.labels.len(), 2);
+    assert_eq!(config.labels["team"], "core");
+
+    let config = build(&[
+        ("LABELS", "team=core,tier=1"),
+        ("APP_LABELS__TEAM", "platform"),
+    ]);
+    assert_eq!(
+        config.labels,
+        HashMap::from([(String::from("team"), String::from("platform"))])
+    );
+}
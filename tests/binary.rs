@@ -0,0 +1,103 @@
+use std::ops::Not;
+
+use envious::{BinaryEncoding, EnvDeserializationError, InferredType};
+use serde::Deserialize;
+use serde_bytes::ByteBuf;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Keys {
+    #[serde(with = "serde_bytes")]
+    signing: Vec<u8>,
+    hmac: ByteBuf,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Config {
+    keys: Keys,
+    name: String,
+}
+
+#[test]
+fn decode_binary_encodings() {
+    let cases = [
+        (BinaryEncoding::Base64, "aGk/Pw==", "AP8Q"),
+        (BinaryEncoding::Base64UrlSafe, "aGk_Pw", "AP8Q"),
+        (BinaryEncoding::Hex, "68693F3F", "00ff10"),
+    ];
+
+    for (encoding, signing, hmac) in cases {
+        let config: Config = envious::Config::new()
+            .with_binary_encoding(encoding)
+            .build_from_iter([
+                ("KEYS__SIGNING", signing),
+                ("KEYS__HMAC", hmac),
+                ("NAME", "hi??"),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            config,
+            Config {
+                keys: Keys {
+                    signing: b"hi??".to_vec(),
+                    hmac: ByteBuf::from(vec![0x00, 0xff, 0x10]),
+                },
+                name: String::from("hi??"),
+            }
+        );
+    }
+}
+
+#[test]
+fn bytes_without_encoding() {
+    let config: Config = envious::Config::new()
+        .build_from_iter([
+            ("KEYS__SIGNING", "hi??"),
+            ("KEYS__HMAC", "AP8Q"),
+            ("NAME", "hi??"),
+        ])
+        .unwrap();
+
+    assert_eq!(config.keys.signing, b"hi??");
+    assert_eq!(config.keys.hmac, ByteBuf::from(b"AP8Q".to_vec()));
+}
+
+#[test]
+fn bytes_without_encoding_are_not_inferred() {
+    let config: Config = envious::Config::new()
+        .with_type_inference(InferredType::DEFAULT_PRECEDENCE)
+        .build_from_iter([
+            ("KEYS__SIGNING", "1234"),
+            ("KEYS__HMAC", "true"),
+            ("NAME", "hi??"),
+        ])
+        .unwrap();
+
+    assert_eq!(config.keys.signing, b"1234");
+    assert_eq!(config.keys.hmac, ByteBuf::from(b"true".to_vec()));
+}
+
+#[test]
+fn decode_errors_do_not_contain_values() {
+    let error = envious::Config::new()
+        .with_prefix("APP_")
+        .with_binary_encoding(BinaryEncoding::Base64)
+        .build_from_iter::<Config, _, _, _>([
+            ("APP_KEYS__SIGNING", "s3cr3t!value"),
+            ("APP_KEYS__HMAC", "AP8Q"),
+            ("APP_NAME", "app"),
+        ])
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        EnvDeserializationError::InVariable {
+            variable: String::from("APP_KEYS__SIGNING"),
+            path: vec![String::from("keys"), String::from("signing")],
+            source: Box::new(EnvDeserializationError::InvalidBinary(String::from(
+                "invalid base64 character at position 6"
+            ))),
+        }
+    );
+    assert!(error.to_string().contains("s3cr3t").not());
+}
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::ops::Not;
+
+use envious::EnvDeserializationError;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Config {
+    debug: bool,
+    verbose: bool,
+    metrics: bool,
+    tracing: Option<bool>,
+}
+
+#[test]
+fn parse_common_spellings() {
+    let vars = [
+        ("DEBUG", "TRUE"),
+        ("VERBOSE", "yes"),
+        ("METRICS", "Off"),
+        ("TRACING", "1"),
+    ];
+
+    let config: Config = envious::Config::new().build_from_iter(vars).unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            debug: true,
+            verbose: true,
+            metrics: false,
+            tracing: Some(true),
+        }
+    );
+}
+
+#[test]
+fn custom_spellings_follow_case_sensitivity() {
+    let vars = [
+        ("debug", "Enabled"),
+        ("verbose", "disabled"),
+        ("metrics", "enabled"),
+    ];
+
+    let config: Config = envious::Config::new()
+        .with_bool_spellings(["enabled"], ["disabled"])
+        .build_from_iter(vars)
+        .unwrap();
+
+    assert!(config.debug);
+    assert!(config.verbose.not());
+
+    let error = envious::Config::new()
+        .case_sensitive(true)
+        .with_bool_spellings(["enabled"], ["disabled"])
+        .build_from_iter::<Config, _, _, _>(vars)
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        EnvDeserializationError::InVariable {
+            variable: String::from("debug"),
+            path: vec![String::from("debug")],
+            source: Box::new(EnvDeserializationError::GenericDeserialization(
+                String::from(
+                    "'Enabled' could not be deserialized due to: expected one of `enabled`, `disabled`"
+                )
+            )),
+        }
+    );
+}
+
+#[test]
+fn strict_bools_only_accept_true_and_false() {
+    let vars = [("DEBUG", "true"), ("VERBOSE", "false"), ("METRICS", "yes")];
+
+    let error = envious::Config::new()
+        .strict_bools(true)
+        .build_from_iter::<Config, _, _, _>(vars)
+        .unwrap_err();
+
+    assert!(matches!(
+        error,
+        EnvDeserializationError::InVariable { variable, .. } if variable == "METRICS"
+    ));
+}
+
+#[test]
+fn map_keys_use_the_same_spellings() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Config {
+        features: HashMap<bool, String>,
+    }
+
+    let vars = [("FEATURES__ON", "enabled"), ("FEATURES__0", "disabled")];
+
+    let config: Config = envious::Config::new().build_from_iter(vars).unwrap();
+
+    assert_eq!(
+        config.features,
+        HashMap::from([
+            (true, String::from("enabled")),
+            (false, String::from("disabled")),
+        ])
+    );
+}
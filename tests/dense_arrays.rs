@@ -0,0 +1,152 @@
+use envious::EnvDeserializationError;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+struct Config {
+    servers: Vec<String>,
+    #[serde(default)]
+    pair: [u8; 2],
+}
+
+fn build(vars: &[(&str, &str)]) -> Result<Config, EnvDeserializationError> {
+    envious::Config::new()
+        .dense_arrays(true)
+        .build_from_iter(vars.iter().copied())
+}
+
+fn failing_variable(error: EnvDeserializationError) -> (String, EnvDeserializationError) {
+    match error {
+        EnvDeserializationError::InVariable {
+            variable, source, ..
+        } => (variable, *source),
+        error => panic!("Unexpected error: {:?}", error),
+    }
+}
+
+#[test]
+fn dense_indices() {
+    let config = build(&[
+        ("SERVERS__10", "k"),
+        ("SERVERS__2", "c"),
+        ("SERVERS__0", "a"),
+        ("SERVERS__1", "b"),
+        ("SERVERS__3", "d"),
+        ("SERVERS__4", "e"),
+        ("SERVERS__5", "f"),
+        ("SERVERS__6", "g"),
+        ("SERVERS__7", "h"),
+        ("SERVERS__8", "i"),
+        ("SERVERS__9", "j"),
+        ("PAIR__1", "2"),
+        ("PAIR__0", "1"),
+    ])
+    .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            servers: "abcdefghijk".chars().map(String::from).collect(),
+            pair: [1, 2],
+        }
+    );
+}
+
+#[test]
+fn gaps_are_rejected() {
+    let error = build(&[("SERVERS__0", "a"), ("SERVERS__2", "c")]).unwrap_err();
+
+    assert_eq!(
+        error,
+        EnvDeserializationError::MissingVariable {
+            variable: String::from("SERVERS__1"),
+            path: vec![String::from("servers"), String::from("1")],
+        }
+    );
+}
+
+#[test]
+fn invalid_indices_are_rejected() {
+    let error = build(&[("SERVERS__0", "a"), ("SERVERS__1b", "b")]).unwrap_err();
+
+    assert_eq!(
+        failing_variable(error),
+        (
+            String::from("SERVERS__1b"),
+            EnvDeserializationError::InvalidArrayIndex(String::from("1b"))
+        )
+    );
+
+    let error = build(&[("SERVERS__0", "a"), ("SERVERS__-1", "b")]).unwrap_err();
+
+    assert_eq!(
+        failing_variable(error),
+        (
+            String::from("SERVERS__-1"),
+            EnvDeserializationError::InvalidArrayIndex(String::from("-1"))
+        )
+    );
+}
+
+#[test]
+fn duplicate_indices_are_rejected() {
+    let error = build(&[
+        ("SERVERS__1", "a"),
+        ("SERVERS__0", "b"),
+        ("SERVERS__01", "c"),
+    ])
+    .unwrap_err();
+
+    assert_eq!(
+        failing_variable(error),
+        (
+            String::from("SERVERS__01"),
+            EnvDeserializationError::DuplicateArrayIndex(1)
+        )
+    );
+}
+
+#[test]
+fn fixed_size_arrays_are_checked() {
+    let error = build(&[
+        ("SERVERS__0", "a"),
+        ("PAIR__0", "1"),
+        ("PAIR__1", "2"),
+        ("PAIR__2", "3"),
+    ])
+    .unwrap_err();
+
+    assert_eq!(failing_variable(error).0, "PAIR__2");
+
+    let error = build(&[("SERVERS__0", "a"), ("PAIR__1", "2")]).unwrap_err();
+
+    assert_eq!(
+        error,
+        EnvDeserializationError::MissingVariable {
+            variable: String::from("PAIR__0"),
+            path: vec![String::from("pair"), String::from("0")],
+        }
+    );
+}
+
+#[test]
+fn huge_indices_are_rejected_as_gaps() {
+    let error = build(&[("SERVERS__0", "a"), ("SERVERS__18446744073709551615", "b")]).unwrap_err();
+
+    assert_eq!(
+        error,
+        EnvDeserializationError::MissingVariable {
+            variable: String::from("SERVERS__1"),
+            path: vec![String::from("servers"), String::from("1")],
+        }
+    );
+
+    let error = build(&[("SERVERS__100000000000", "b")]).unwrap_err();
+
+    assert_eq!(
+        error,
+        EnvDeserializationError::MissingVariable {
+            variable: String::from("SERVERS__0"),
+            path: vec![String::from("servers"), String::from("0")],
+        }
+    );
+}
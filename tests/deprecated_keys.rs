@@ -0,0 +1,214 @@
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use envious::EnvDeserializationError;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Database {
+    url: String,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Config {
+    database: Database,
+}
+
+thread_local! {
+    static WARNINGS: RefCell<Vec<(String, String)>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record_warning(variable: &str, replacement: &str) {
+    WARNINGS.with(|warnings| {
+        warnings
+            .borrow_mut()
+            .push((variable.to_owned(), replacement.to_owned()))
+    });
+}
+
+fn take_warnings() -> Vec<(String, String)> {
+    WARNINGS.with(|warnings| warnings.take())
+}
+
+#[test]
+fn read_deprecated_keys() {
+    let config: Config = envious::Config::new()
+        .with_prefix("APP_")
+        .with_deprecated_key("db_url", "database__url")
+        .on_deprecated(record_warning)
+        .build_from_iter([("APP_DB_URL", "postgres://db/app")])
+        .unwrap();
+
+    assert_eq!(config.database.url, "postgres://db/app");
+    assert_eq!(
+        take_warnings(),
+        [(
+            String::from("APP_DB_URL"),
+            String::from("APP_DATABASE__URL")
+        )]
+    );
+}
+
+#[test]
+fn replacements_take_precedence() {
+    let config: Config = envious::Config::new()
+        .with_prefix("APP_")
+        .with_deprecated_key("db_url", "database__url")
+        .on_deprecated(record_warning)
+        .strict(true)
+        .build_from_iter([
+            ("APP_DB_URL", "postgres://old/app"),
+            ("APP_DATABASE__URL", "postgres://db/app"),
+        ])
+        .unwrap();
+
+    assert_eq!(config.database.url, "postgres://db/app");
+    assert_eq!(take_warnings().len(), 1);
+}
+
+#[test]
+fn deprecated_keys_before_their_cutoff() {
+    let cutoff = SystemTime::now() + Duration::from_secs(3600);
+
+    let config: Config = envious::Config::new()
+        .with_deprecated_key_until("db_url", "database__url", cutoff)
+        .on_deprecated(record_warning)
+        .build_from_iter([("DB_URL", "postgres://db/app")])
+        .unwrap();
+
+    assert_eq!(config.database.url, "postgres://db/app");
+    assert_eq!(take_warnings().len(), 1);
+}
+
+#[test]
+fn deprecated_keys_after_their_cutoff() {
+    let error = envious::Config::new()
+        .with_prefix("APP_")
+        .with_deprecated_key_until("db_url", "database__url", SystemTime::UNIX_EPOCH)
+        .on_deprecated(record_warning)
+        .build_from_iter::<Config, _, _, _>([("APP_DB_URL", "postgres://db/app")])
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        EnvDeserializationError::DeprecatedVariable {
+            variable: String::from("APP_DB_URL"),
+            replacement: String::from("APP_DATABASE__URL"),
+        }
+    );
+    assert!(take_warnings().is_empty());
+}
+
+#[test]
+fn accumulate_deprecated_keys_after_their_cutoff() {
+    let error = envious::Config::new()
+        .with_deprecated_key_until("db_url", "database__url", SystemTime::UNIX_EPOCH)
+        .accumulate_errors(true)
+        .build_from_iter::<Config, _, _, _>([("DB_URL", "postgres://db/app")])
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        EnvDeserializationError::Multiple(vec![
+            EnvDeserializationError::DeprecatedVariable {
+                variable: String::from("DB_URL"),
+                replacement: String::from("DATABASE__URL"),
+            },
+            EnvDeserializationError::MissingVariable {
+                variable: String::from("DATABASE"),
+                path: vec![String::from("database")],
+            },
+        ])
+    );
+}
+
+#[test]
+fn keys_below_deprecated_keys_are_replaced() {
+    let warnings = Arc::new(Mutex::new(vec![]));
+    let recorded = warnings.clone();
+
+    let config: Config = envious::Config::new()
+        .with_prefix("APP_")
+        .with_deprecated_key("db", "database")
+        .on_deprecated(move |variable, replacement| {
+            recorded
+                .lock()
+                .unwrap()
+                .push((variable.to_owned(), replacement.to_owned()))
+        })
+        .build_from_iter([("APP_DB__URL", "postgres://db/app")])
+        .unwrap();
+
+    assert_eq!(config.database.url, "postgres://db/app");
+    assert_eq!(
+        *warnings.lock().unwrap(),
+        [(
+            String::from("APP_DB__URL"),
+            String::from("APP_DATABASE__URL")
+        )]
+    );
+}
+
+#[test]
+fn nested_replacements_take_precedence() {
+    let config: Config = envious::Config::new()
+        .with_prefix("APP_")
+        .with_deprecated_key("db", "database")
+        .on_deprecated(record_warning)
+        .strict(true)
+        .build_from_iter([
+            ("APP_DB", "postgres://old/app"),
+            ("APP_DATABASE__URL", "postgres://db/app"),
+        ])
+        .unwrap();
+
+    assert_eq!(config.database.url, "postgres://db/app");
+    assert_eq!(take_warnings().len(), 1);
+}
+
+#[test]
+fn deprecated_keys_take_precedence_over_fallback_prefixes() {
+    let config: Config = envious::Config::new()
+        .with_prefix("BILLING_")
+        .with_fallback_prefix("COMMON_")
+        .with_deprecated_key("db_url", "database__url")
+        .on_deprecated(record_warning)
+        .build_from_iter([
+            ("BILLING_DB_URL", "postgres://db/billing"),
+            ("COMMON_DATABASE__URL", "postgres://db/common"),
+        ])
+        .unwrap();
+
+    assert_eq!(config.database.url, "postgres://db/billing");
+    assert_eq!(
+        take_warnings(),
+        [(
+            String::from("BILLING_DB_URL"),
+            String::from("BILLING_DATABASE__URL")
+        )]
+    );
+}
+
+#[test]
+fn fallback_prefixes_are_replaced_with_their_own_prefix() {
+    let config: Config = envious::Config::new()
+        .with_prefix("BILLING_")
+        .with_fallback_prefix("COMMON_")
+        .with_deprecated_key("db_url", "database__url")
+        .on_deprecated(record_warning)
+        .build_from_iter([
+            ("BILLING_DATABASE__URL", "postgres://db/billing"),
+            ("COMMON_DB_URL", "postgres://db/common"),
+        ])
+        .unwrap();
+
+    assert_eq!(config.database.url, "postgres://db/billing");
+    assert_eq!(
+        take_warnings(),
+        [(
+            String::from("COMMON_DB_URL"),
+            String::from("COMMON_DATABASE__URL")
+        )]
+    );
+}
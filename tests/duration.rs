@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use envious::EnvDeserializationError;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Config {
+    timeout: Duration,
+    interval: Duration,
+    grace: Option<Duration>,
+    #[serde(default)]
+    retries: Vec<Duration>,
+}
+
+#[test]
+fn parse_human_readable_durations() {
+    let vars = [
+        ("TIMEOUT", "5s"),
+        ("INTERVAL", "1h30m"),
+        ("GRACE", "1.5"),
+        ("RETRIES__0", "250ms"),
+        ("RETRIES__1", "2m"),
+    ];
+
+    let config: Config = envious::Config::new().build_from_iter(vars).unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            timeout: Duration::from_secs(5),
+            interval: Duration::from_secs(90 * 60),
+            grace: Some(Duration::from_millis(1500)),
+            retries: vec![Duration::from_millis(250), Duration::from_secs(120)],
+        }
+    );
+}
+
+#[test]
+fn nested_durations_still_work() {
+    let vars = [
+        ("TIMEOUT__SECS", "5"),
+        ("TIMEOUT__NANOS", "0"),
+        ("INTERVAL", "10ms"),
+    ];
+
+    let config: Config = envious::Config::new().build_from_iter(vars).unwrap();
+
+    assert_eq!(config.timeout, Duration::from_secs(5));
+    assert_eq!(config.grace, None);
+    assert_eq!(config.retries, vec![]);
+}
+
+#[test]
+fn invalid_duration_names_variable() {
+    let vars = [("APP_TIMEOUT", "5 seconds"), ("APP_INTERVAL", "1s")];
+
+    let error = envious::Config::new()
+        .with_prefix("APP_")
+        .build_from_iter::<Config, _, _, _>(vars)
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        EnvDeserializationError::InVariable {
+            variable: String::from("APP_TIMEOUT"),
+            path: vec![String::from("timeout")],
+            source: Box::new(EnvDeserializationError::InvalidDuration(String::from(
+                "5 seconds"
+            ))),
+        }
+    );
+}
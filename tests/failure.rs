@@ -23,3 +23,96 @@ fn wrongly_nested_prefixed_fields() {
 
     println!("{:?}", config.unwrap_err());
 }
+
+#[derive(Deserialize, Debug)]
+struct Pool {
+    max: u32,
+}
+
+#[derive(Deserialize, Debug)]
+struct Database {
+    pool: Pool,
+}
+
+#[derive(Deserialize, Debug)]
+struct App {
+    database: Database,
+}
+
+#[test]
+fn error_names_variable() {
+    let vars = [("MYAPP_DATABASE__POOL__MAX", "abc")];
+
+    let error = envious::Config::new()
+        .with_prefix("myapp_")
+        .build_from_iter::<App, _, _, _>(vars)
+        .unwrap_err();
+
+    match error {
+        envious::EnvDeserializationError::InVariable { variable, path, .. } => {
+            assert_eq!(variable, "MYAPP_DATABASE__POOL__MAX");
+            assert_eq!(path, ["database", "pool", "max"]);
+        }
+        error => panic!("Unexpected error: {:?}", error),
+    }
+}
+
+#[test]
+fn nesting_error_names_variable() {
+    let vars = [("Test", "true"), ("Test__bar", "true")];
+
+    let error = envious::Config::new()
+        .build_from_iter::<Simple, _, _, _>(vars)
+        .unwrap_err();
+
+    match error {
+        envious::EnvDeserializationError::InVariable { variable, .. } => {
+            assert_eq!(variable, "Test__bar");
+        }
+        error => panic!("Unexpected error: {:?}", error),
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+#[derive(Deserialize, Debug)]
+struct WithServer {
+    server: Server,
+}
+
+#[test]
+fn missing_field_names_variable() {
+    let vars = [("app_server__host", "localhost")];
+
+    let error = envious::Config::new()
+        .with_prefix("app_")
+        .build_from_iter::<WithServer, _, _, _>(vars)
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        envious::EnvDeserializationError::MissingVariable {
+            variable: String::from("APP_SERVER__PORT"),
+            path: vec![String::from("server"), String::from("port")],
+        }
+    );
+
+    let error = envious::Config::new()
+        .case_sensitive(true)
+        .with_prefix("app_")
+        .with_separator("_")
+        .build_from_iter::<Simple, _, _, _>(vars)
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        envious::EnvDeserializationError::MissingVariable {
+            variable: String::from("app_test"),
+            path: vec![String::from("test")],
+        }
+    );
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use envious::InferredType;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Tls {
+    enabled: bool,
+    cert: String,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Common {
+    port: u16,
+    ratio: f32,
+    verbose: bool,
+    timeout: Option<u64>,
+    tls: Tls,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Server {
+    name: String,
+    #[serde(flatten)]
+    common: Common,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+enum Backend {
+    Local {
+        path: String,
+        #[serde(flatten)]
+        common: Common,
+    },
+    None,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(tag = "kind")]
+enum Tagged {
+    Remote {
+        #[serde(flatten)]
+        common: Common,
+    },
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Config {
+    server: Server,
+    backend: Backend,
+    tagged: Tagged,
+    #[serde(flatten)]
+    limits: HashMap<String, u32>,
+}
+
+fn common() -> Common {
+    Common {
+        port: 8080,
+        ratio: 0.5,
+        verbose: true,
+        timeout: Some(30),
+        tls: Tls {
+            enabled: false,
+            cert: String::from("cert.pem"),
+        },
+    }
+}
+
+#[test]
+fn parse_flattened() {
+    let mut vars = vec![
+        (String::from("server__name"), "main"),
+        (String::from("backend__Local__path"), "/data"),
+        (String::from("tagged__kind"), "Remote"),
+        (String::from("max_connections"), "100"),
+        (String::from("max_requests"), "2000"),
+    ];
+    for prefix in ["server__", "backend__Local__", "tagged__"] {
+        vars.extend(
+            [
+                ("port", "8080"),
+                ("ratio", "0.5"),
+                ("verbose", "true"),
+                ("timeout", "30"),
+                ("tls__enabled", "false"),
+                ("tls__cert", "cert.pem"),
+            ]
+            .map(|(key, value)| (format!("{}{}", prefix, key), value)),
+        );
+    }
+
+    let config: Config = envious::Config::new()
+        .with_type_inference(InferredType::DEFAULT_PRECEDENCE)
+        .build_from_iter(vars)
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            server: Server {
+                name: String::from("main"),
+                common: common(),
+            },
+            backend: Backend::Local {
+                path: String::from("/data"),
+                common: common(),
+            },
+            tagged: Tagged::Remote { common: common() },
+            limits: HashMap::from([
+                (String::from("max_connections"), 100),
+                (String::from("max_requests"), 2000),
+            ]),
+        }
+    );
+}
+
+#[test]
+fn flattened_null_markers() {
+    let vars = [
+        ("name", "main"),
+        ("port", "8080"),
+        ("ratio", "1"),
+        ("verbose", "false"),
+        ("timeout", "null"),
+        ("tls__enabled", "true"),
+        ("tls__cert", "cert.pem"),
+    ];
+
+    let config: Server = envious::Config::new()
+        .with_type_inference(InferredType::DEFAULT_PRECEDENCE)
+        .with_null_markers(["null"])
+        .build_from_iter(vars)
+        .unwrap();
+
+    assert_eq!(config.common.timeout, None);
+    assert_eq!(config.common.ratio, 1.0);
+}
+
+#[test]
+fn flattened_strings_without_inference() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Flat {
+        name: String,
+        #[serde(flatten)]
+        extra: HashMap<String, String>,
+    }
+
+    let vars = [("name", "1234"), ("other", "80"), ("enabled", "true")];
+
+    let config: Flat = envious::Config::new().build_from_iter(vars).unwrap();
+
+    assert_eq!(
+        config,
+        Flat {
+            name: String::from("1234"),
+            extra: HashMap::from([
+                (String::from("other"), String::from("80")),
+                (String::from("enabled"), String::from("true")),
+            ]),
+        }
+    );
+}
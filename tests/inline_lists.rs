@@ -0,0 +1,152 @@
+use envious::EnvDeserializationError;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, PartialEq)]
+enum Protocol {
+    Http,
+    Https,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Server {
+    allowed_hosts: Vec<String>,
+    ports: Vec<u16>,
+    protocols: Vec<Protocol>,
+    origin: (String, u16),
+}
+
+#[test]
+fn parse_inline_lists() {
+    let vars = [
+        ("allowed_hosts", "a.example.com,b.example.com"),
+        ("ports", "80,443"),
+        ("protocols", "Http,Https"),
+        ("origin", "localhost,8080"),
+    ];
+
+    let config: Server = envious::Config::new()
+        .with_list_delimiter(",")
+        .build_from_iter(vars)
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Server {
+            allowed_hosts: vec![String::from("a.example.com"), String::from("b.example.com")],
+            ports: vec![80, 443],
+            protocols: vec![Protocol::Http, Protocol::Https],
+            origin: (String::from("localhost"), 8080),
+        }
+    );
+}
+
+#[test]
+fn escaping_and_trimming() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Config {
+        values: Vec<String>,
+        empty: Vec<u32>,
+    }
+
+    let config: Config = envious::Config::new()
+        .with_list_delimiter(";")
+        .trim_list_elements(true)
+        .build_from_iter([("values", r" a\;b ; c\\ ;d "), ("empty", " ")])
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            values: vec![String::from("a;b"), String::from(r"c\"), String::from("d")],
+            empty: vec![],
+        }
+    );
+}
+
+#[test]
+fn delimiter_per_path() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Config {
+        server: Server,
+        tags: Vec<String>,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Server {
+        allowed_hosts: Vec<String>,
+    }
+
+    let config: Config = envious::Config::new()
+        .with_list_delimiter(",")
+        .with_list_delimiter_for("server.allowed_hosts", " ")
+        .build_from_iter([("SERVER__ALLOWED_HOSTS", "a,1 b,2"), ("TAGS", "x,y")])
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            server: Server {
+                allowed_hosts: vec![String::from("a,1"), String::from("b,2")],
+            },
+            tags: vec![String::from("x"), String::from("y")],
+        }
+    );
+}
+
+#[test]
+fn indexed_and_inline_mixed() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Config {
+        inline: Vec<u8>,
+        indexed: Vec<u8>,
+    }
+
+    let config: Config = envious::Config::new()
+        .with_list_delimiter(",")
+        .build_from_iter([("inline", "1,2"), ("indexed__0", "3"), ("indexed__1", "4")])
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            inline: vec![1, 2],
+            indexed: vec![3, 4],
+        }
+    );
+}
+
+#[test]
+fn invalid_element_names_variable() {
+    let error = envious::Config::new()
+        .with_list_delimiter(",")
+        .build_from_iter::<Server, _, _, _>([
+            ("ALLOWED_HOSTS", "a"),
+            ("PORTS", "80,http"),
+            ("PROTOCOLS", "Http"),
+            ("ORIGIN", "localhost,80"),
+        ])
+        .unwrap_err();
+
+    match error {
+        EnvDeserializationError::InVariable { variable, path, .. } => {
+            assert_eq!(variable, "PORTS");
+            assert_eq!(path, ["ports", "1"]);
+        }
+        error => panic!("Unexpected error: {}", error),
+    }
+
+    let error = envious::Config::new()
+        .with_list_delimiter(",")
+        .build_from_iter::<Server, _, _, _>([
+            ("ALLOWED_HOSTS", "a"),
+            ("PORTS", "80"),
+            ("PROTOCOLS", "Http"),
+            ("ORIGIN", "localhost,80,81"),
+        ])
+        .unwrap_err();
+
+    assert!(matches!(
+        error,
+        EnvDeserializationError::InVariable { variable, .. } if variable == "ORIGIN"
+    ));
+}
@@ -0,0 +1,147 @@
+use std::collections::{BTreeMap, HashMap};
+
+use envious::EnvDeserializationError;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Config {
+    labels: HashMap<String, String>,
+    limits: BTreeMap<String, u32>,
+}
+
+#[test]
+fn parse_inline_maps() {
+    let vars = [
+        ("LABELS", "team=core,tier=1"),
+        ("LIMITS", "cpu=2,memory=512"),
+    ];
+
+    let config: Config = envious::Config::new()
+        .with_inline_maps(",", "=")
+        .build_from_iter(vars)
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            labels: HashMap::from([
+                (String::from("team"), String::from("core")),
+                (String::from("tier"), String::from("1")),
+            ]),
+            limits: BTreeMap::from([(String::from("cpu"), 2), (String::from("memory"), 512)]),
+        }
+    );
+}
+
+#[test]
+fn nested_keys_override_inline_entries() {
+    for vars in [
+        [
+            ("LABELS", "team=core,tier=1"),
+            ("LABELS__TIER", "2"),
+            ("LABELS__ZONE", "eu"),
+            ("LIMITS", ""),
+        ],
+        [
+            ("LABELS__TIER", "2"),
+            ("LABELS__ZONE", "eu"),
+            ("LABELS", "team=core,tier=1"),
+            ("LIMITS", ""),
+        ],
+    ] {
+        let config: Config = envious::Config::new()
+            .with_inline_maps(",", "=")
+            .build_from_iter(vars)
+            .unwrap();
+
+        assert_eq!(
+            config,
+            Config {
+                labels: HashMap::from([
+                    (String::from("team"), String::from("core")),
+                    (String::from("tier"), String::from("2")),
+                    (String::from("zone"), String::from("eu")),
+                ]),
+                limits: BTreeMap::new(),
+            }
+        );
+    }
+}
+
+#[test]
+fn escaping_and_trimming() {
+    let config: Config = envious::Config::new()
+        .with_inline_maps(";", ":")
+        .trim_list_elements(true)
+        .build_from_iter([
+            ("labels", r" url : http\://a\;b ; path\\: c:d "),
+            ("limits", "cpu: 2"),
+        ])
+        .unwrap();
+
+    assert_eq!(
+        config.labels,
+        HashMap::from([
+            (String::from("url"), String::from("http://a;b")),
+            (String::from(r"path\"), String::from("c:d")),
+        ])
+    );
+}
+
+#[test]
+fn invalid_entries_name_variable() {
+    let error = envious::Config::new()
+        .with_inline_maps(",", "=")
+        .build_from_iter::<Config, _, _, _>([("LABELS", "team=core,tier"), ("LIMITS", "cpu=2")])
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        EnvDeserializationError::InVariable {
+            variable: String::from("LABELS"),
+            path: vec![String::from("labels")],
+            source: Box::new(EnvDeserializationError::InvalidMapEntry(String::from(
+                "tier"
+            ))),
+        }
+    );
+
+    let error = envious::Config::new()
+        .with_inline_maps(",", "=")
+        .build_from_iter::<Config, _, _, _>([
+            ("LABELS", "team=core"),
+            ("LIMITS", "cpu=two"),
+            ("LIMITS__MEMORY", "512"),
+        ])
+        .unwrap_err();
+
+    match error {
+        EnvDeserializationError::InVariable { variable, path, .. } => {
+            assert_eq!(variable, "LIMITS");
+            assert_eq!(path, ["limits", "cpu"]);
+        }
+        error => panic!("Unexpected error: {}", error),
+    }
+}
+
+#[test]
+fn simple_values_next_to_nested_ones_are_invalid_nesting() {
+    let error = envious::Config::new()
+        .with_inline_maps(",", "=")
+        .build_from_iter::<Config, _, _, _>([
+            ("LABELS", "true"),
+            ("LABELS__TEAM", "core"),
+            ("LIMITS", "cpu=2"),
+        ])
+        .unwrap_err();
+
+    let path = vec![String::from("labels"), String::from("team")];
+    assert_eq!(
+        error,
+        EnvDeserializationError::InVariable {
+            variable: String::from("LABELS__TEAM"),
+            path: path.clone(),
+            source: Box::new(EnvDeserializationError::InvalidEnvNesting(path)),
+        }
+    );
+}
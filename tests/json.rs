@@ -0,0 +1,168 @@
+#![cfg(feature = "json")]
+
+use std::collections::HashMap;
+
+use envious::EnvDeserializationError;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Route {
+    path: String,
+    to: String,
+    weight: Option<u8>,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+enum Backend {
+    Static { root: String },
+    Proxy(String),
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Config {
+    routes: Vec<Route>,
+    features: HashMap<String, Vec<bool>>,
+    backend: Backend,
+    fallback: Route,
+}
+
+#[test]
+fn parse_json_values() {
+    let vars = [
+        (
+            "ROUTES",
+            r#"[{"path": "/", "to": "svc", "weight": 3}, {"path": "/api", "to": "api"}]"#,
+        ),
+        ("FEATURES", r#"{"beta": [true, false], "alpha": []}"#),
+        ("BACKEND", r#"{"Static": {"root": "/srv"}}"#),
+        (
+            "FALLBACK",
+            r#"{"path": "/", "to": "default", "weight": null}"#,
+        ),
+    ];
+
+    let config: Config = envious::Config::new()
+        .json_values(true)
+        .build_from_iter(vars)
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            routes: vec![
+                Route {
+                    path: String::from("/"),
+                    to: String::from("svc"),
+                    weight: Some(3),
+                },
+                Route {
+                    path: String::from("/api"),
+                    to: String::from("api"),
+                    weight: None,
+                },
+            ],
+            features: HashMap::from([
+                (String::from("beta"), vec![true, false]),
+                (String::from("alpha"), vec![]),
+            ]),
+            backend: Backend::Static {
+                root: String::from("/srv")
+            },
+            fallback: Route {
+                path: String::from("/"),
+                to: String::from("default"),
+                weight: None,
+            },
+        }
+    );
+}
+
+#[test]
+fn nested_variables_override_json() {
+    let vars = [
+        ("ROUTES__0__TO", "other"),
+        (
+            "ROUTES",
+            r#"[{"path": "/", "to": "svc", "weight": 3}, {"path": "/api", "to": "api"}]"#,
+        ),
+        ("ROUTES__1__WEIGHT", "7"),
+        ("FEATURES", r#"{"beta": [true]}"#),
+        ("FEATURES__BETA__0", "false"),
+        ("BACKEND__PROXY", "http://localhost"),
+        ("FALLBACK__PATH", "/"),
+        ("FALLBACK__TO", "default"),
+    ];
+
+    let config: Config = envious::Config::new()
+        .json_values(true)
+        .build_from_iter(vars)
+        .unwrap();
+
+    assert_eq!(
+        config.routes,
+        vec![
+            Route {
+                path: String::from("/"),
+                to: String::from("other"),
+                weight: Some(3),
+            },
+            Route {
+                path: String::from("/api"),
+                to: String::from("api"),
+                weight: Some(7),
+            },
+        ]
+    );
+    assert_eq!(
+        config.features,
+        HashMap::from([(String::from("beta"), vec![false])])
+    );
+    assert_eq!(
+        config.backend,
+        Backend::Proxy(String::from("http://localhost"))
+    );
+}
+
+#[test]
+fn errors_name_json_variable() {
+    let vars = [
+        (
+            "ROUTES",
+            r#"[{"path": "/", "to": "svc", "weight": "heavy"}]"#,
+        ),
+        ("FEATURES", "{}"),
+        ("BACKEND__PROXY", "http://localhost"),
+        ("FALLBACK", r#"{"path": "/", "to": "default"}"#),
+    ];
+
+    let error = envious::Config::new()
+        .json_values(true)
+        .build_from_iter::<Config, _, _, _>(vars)
+        .unwrap_err();
+
+    match error {
+        EnvDeserializationError::InVariable { variable, path, .. } => {
+            assert_eq!(variable, "ROUTES");
+            assert_eq!(path, ["routes", "0", "weight"]);
+        }
+        error => panic!("Unexpected error: {}", error),
+    }
+
+    let vars = [
+        ("ROUTES", "[]"),
+        ("FEATURES", "{"),
+        ("BACKEND__PROXY", "http://localhost"),
+        ("FALLBACK", r#"{"path": "/", "to": "default"}"#),
+    ];
+
+    let error = envious::Config::new()
+        .json_values(true)
+        .build_from_iter::<Config, _, _, _>(vars)
+        .unwrap_err();
+
+    assert!(matches!(
+        error,
+        EnvDeserializationError::InVariable { variable, source, .. }
+            if variable == "FEATURES" && matches!(*source, EnvDeserializationError::InvalidJson(_))
+    ));
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+
+use envious::{EnvDeserializationError, KeyEncoding};
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Upstream {
+    port: u16,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Config {
+    upstreams: HashMap<String, Upstream>,
+    paths: HashMap<String, String>,
+}
+
+#[test]
+fn parse_hex_encoded_keys() {
+    let vars = [
+        ("APP_UPSTREAMS__API_x2EEXAMPLE_x2ECOM__PORT", "443"),
+        ("APP_UPSTREAMS__localhost__PORT", "8080"),
+        ("APP_PATHS___x2Fvar_x2Flib", "data"),
+    ];
+
+    let config: Config = envious::Config::new()
+        .with_prefix("APP_")
+        .with_key_encoding(KeyEncoding::Hex)
+        .build_from_iter(vars)
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            upstreams: HashMap::from([
+                (String::from("api.example.com"), Upstream { port: 443 }),
+                (String::from("localhost"), Upstream { port: 8080 }),
+            ]),
+            paths: HashMap::from([(String::from("/var/lib"), String::from("data"))]),
+        }
+    );
+}
+
+#[test]
+fn parse_percent_encoded_keys() {
+    let vars = [
+        ("upstreams__api%2Eexample%2Ecom__port", "443"),
+        ("paths__my%2Dkey", "value"),
+    ];
+
+    let config: Config = envious::Config::new()
+        .case_sensitive(true)
+        .with_key_encoding(KeyEncoding::Percent)
+        .build_from_iter(vars)
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            upstreams: HashMap::from([(String::from("api.example.com"), Upstream { port: 443 })]),
+            paths: HashMap::from([(String::from("my-key"), String::from("value"))]),
+        }
+    );
+}
+
+#[test]
+fn keys_are_not_decoded_by_default() {
+    let config: Config = envious::Config::new()
+        .build_from_iter([("upstreams__a__port", "80"), ("paths__a_x2E_b", "value")])
+        .unwrap();
+
+    assert_eq!(
+        config.paths,
+        HashMap::from([(String::from("a_x2e_b"), String::from("value"))])
+    );
+}
+
+#[test]
+fn error_names_encoded_variable() {
+    let vars = [
+        ("APP_UPSTREAMS__API_x2EEXAMPLE_x2ECOM__PORT", "https"),
+        ("APP_PATHS__a", "data"),
+    ];
+
+    let error = envious::Config::new()
+        .with_prefix("APP_")
+        .with_key_encoding(KeyEncoding::Hex)
+        .build_from_iter::<Config, _, _, _>(vars)
+        .unwrap_err();
+
+    match error {
+        EnvDeserializationError::InVariable { variable, path, .. } => {
+            assert_eq!(variable, "APP_UPSTREAMS__API_x2EEXAMPLE_x2ECOM__PORT");
+            assert_eq!(path, ["upstreams", "api.example.com", "port"]);
+        }
+        error => panic!("Unexpected error: {}", error),
+    }
+}
+
+#[test]
+fn encoded_keys_round_trip_through_variable_names() {
+    let keys = [
+        "a..b",
+        "https://x",
+        "a_.b",
+        "a._b",
+        "a__b",
+        "trailing_",
+        "ünïcödé",
+    ];
+
+    for encoding in [KeyEncoding::Hex, KeyEncoding::Percent] {
+        let vars = keys
+            .iter()
+            .enumerate()
+            .map(|(port, key)| {
+                (
+                    format!("UPSTREAMS__{}__PORT", encoding.encode(key)),
+                    port.to_string(),
+                )
+            })
+            .chain([(String::from("PATHS__A"), String::from("a"))])
+            .collect::<Vec<_>>();
+
+        let config: Config = envious::Config::new()
+            .with_key_encoding(encoding)
+            .build_from_iter(vars)
+            .unwrap();
+
+        assert_eq!(config.upstreams.len(), keys.len());
+        for (port, key) in keys.iter().enumerate() {
+            assert_eq!(config.upstreams[*key].port, port as u16, "{}", key);
+        }
+    }
+}
+
+#[test]
+fn missing_variables_are_named_with_encoded_keys() {
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Upstream {
+        port: u16,
+        host: String,
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Config {
+        upstreams: HashMap<String, Upstream>,
+    }
+
+    let error = envious::Config::new()
+        .with_prefix("APP_")
+        .with_key_encoding(KeyEncoding::Hex)
+        .build_from_iter::<Config, _, _, _>([("APP_UPSTREAMS__API_x2EEXAMPLE_x2ECOM__PORT", "443")])
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        EnvDeserializationError::MissingVariable {
+            variable: String::from("APP_UPSTREAMS__API_X2EEXAMPLE_X2ECOM__HOST"),
+            path: vec![
+                String::from("upstreams"),
+                String::from("api.example.com"),
+                String::from("host"),
+            ],
+        }
+    );
+}
@@ -0,0 +1,64 @@
+use serde::Deserialize;
+
+fn default_timeout() -> Option<u32> {
+    Some(30)
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+struct Marker;
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+struct Config {
+    #[serde(default = "default_timeout")]
+    timeout: Option<u32>,
+    name: Option<String>,
+    marker: Marker,
+    nothing: (),
+}
+
+#[test]
+fn null_markers_deserialize_as_none() {
+    let vars = [
+        ("timeout", "none"),
+        ("name", ""),
+        ("marker", "none"),
+        ("nothing", ""),
+    ];
+
+    let config: Config = envious::Config::new()
+        .with_null_markers(["", "none"])
+        .build_from_iter(vars)
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            timeout: None,
+            name: None,
+            marker: Marker,
+            nothing: (),
+        }
+    );
+}
+
+#[test]
+fn other_values_deserialize_as_some() {
+    let vars = [("name", "none"), ("marker", ""), ("nothing", "")];
+
+    let config: Config = envious::Config::new()
+        .with_null_markers([""])
+        .build_from_iter(vars)
+        .unwrap();
+
+    assert_eq!(config.timeout, Some(30));
+    assert_eq!(config.name.as_deref(), Some("none"));
+}
+
+#[test]
+fn no_null_markers_by_default() {
+    let vars = [("timeout", ""), ("marker", ""), ("nothing", "")];
+
+    let result: Result<Config, _> = envious::Config::new().build_from_iter(vars);
+
+    result.unwrap_err();
+}
@@ -0,0 +1,108 @@
+use envious::EnvDeserializationError;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Config {
+    mask: u8,
+    permissions: u32,
+    flags: i16,
+    limit: u64,
+    offset: i32,
+    id: u128,
+    delta: i128,
+    ratio: f64,
+}
+
+#[test]
+fn parse_numeric_literals() {
+    let vars = [
+        ("MASK", "0xFF"),
+        ("PERMISSIONS", "0o755"),
+        ("FLAGS", "-0b1010"),
+        ("LIMIT", " 1_000_000 "),
+        ("OFFSET", "+42"),
+        ("ID", "0xFFFF_FFFF_FFFF_FFFF_FFFF_FFFF"),
+        ("DELTA", "-170141183460469231731687303715884105728"),
+        ("RATIO", "1_000.5"),
+    ];
+
+    let config: Config = envious::Config::new()
+        .numeric_literals(true)
+        .build_from_iter(vars)
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            mask: 0xFF,
+            permissions: 0o755,
+            flags: -0b1010,
+            limit: 1_000_000,
+            offset: 42,
+            id: 0xFFFF_FFFF_FFFF_FFFF_FFFF_FFFF,
+            delta: i128::MIN,
+            ratio: 1_000.5,
+        }
+    );
+}
+
+#[test]
+fn numeric_literals_are_opt_in() {
+    #[derive(Deserialize, Debug)]
+    struct Config {
+        #[allow(dead_code)]
+        mask: u8,
+    }
+
+    let error = envious::Config::new()
+        .build_from_iter::<Config, _, _, _>([("MASK", "0xFF")])
+        .unwrap_err();
+
+    assert!(matches!(error, EnvDeserializationError::InVariable { .. }));
+}
+
+#[test]
+fn large_integers_without_literals() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Config {
+        id: u128,
+        delta: i128,
+    }
+
+    let config: Config = envious::Config::new()
+        .build_from_iter([
+            ("ID", "340282366920938463463374607431768211455"),
+            ("DELTA", "-5"),
+        ])
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            id: u128::MAX,
+            delta: -5,
+        }
+    );
+}
+
+#[test]
+fn invalid_digits_are_rejected() {
+    #[derive(Deserialize, Debug)]
+    struct Config {
+        #[allow(dead_code)]
+        mask: u8,
+    }
+
+    for value in ["0b102", "0x", "0x1FF", "0x-1"] {
+        let error = envious::Config::new()
+            .numeric_literals(true)
+            .build_from_iter::<Config, _, _, _>([("MASK", value)])
+            .unwrap_err();
+
+        assert!(
+            matches!(error, EnvDeserializationError::InVariable { .. }),
+            "{} should be rejected",
+            value
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use envious::EnvDeserializationError;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Database {
+    url: String,
+    pool_size: u32,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Config {
+    database: Database,
+    log_level: String,
+}
+
+fn config() -> envious::Config<'static> {
+    let mut config = envious::Config::new();
+    config
+        .with_prefix("BILLING_")
+        .with_fallback_prefix("COMMON_");
+    config
+}
+
+#[test]
+fn merge_prefixes() {
+    let config: Config = config()
+        .build_from_iter([
+            ("COMMON_DATABASE__URL", "postgres://db/common"),
+            ("COMMON_DATABASE__POOL_SIZE", "8"),
+            ("COMMON_LOG_LEVEL", "info"),
+            ("BILLING_DATABASE__URL", "postgres://db/billing"),
+            ("OTHER_LOG_LEVEL", "debug"),
+        ])
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            database: Database {
+                url: String::from("postgres://db/billing"),
+                pool_size: 8,
+            },
+            log_level: String::from("info"),
+        }
+    );
+}
+
+#[test]
+fn nested_values_override_lower_prefixes() {
+    let config: Config = config()
+        .build_from_iter([
+            ("COMMON_DATABASE", "not a database"),
+            ("COMMON_LOG_LEVEL__FILTER", "info"),
+            ("BILLING_DATABASE__URL", "postgres://db/billing"),
+            ("BILLING_DATABASE__POOL_SIZE", "4"),
+            ("BILLING_LOG_LEVEL", "warn"),
+        ])
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            database: Database {
+                url: String::from("postgres://db/billing"),
+                pool_size: 4,
+            },
+            log_level: String::from("warn"),
+        }
+    );
+}
+
+#[test]
+fn errors_name_the_prefix() {
+    let error = config()
+        .build_from_iter::<Config, _, _, _>([
+            ("COMMON_DATABASE__URL", "postgres://db/common"),
+            ("COMMON_DATABASE__POOL_SIZE", "many"),
+            ("BILLING_LOG_LEVEL", "info"),
+        ])
+        .unwrap_err();
+
+    assert!(
+        matches!(
+            &error,
+            EnvDeserializationError::InVariable { variable, .. }
+                if variable == "COMMON_DATABASE__POOL_SIZE"
+        ),
+        "{:?}",
+        error
+    );
+
+    let error = config()
+        .build_from_iter::<Config, _, _, _>([
+            ("COMMON_DATABASE__URL", "postgres://db/common"),
+            ("COMMON_DATABASE__POOL_SIZE", "8"),
+        ])
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        EnvDeserializationError::MissingVariable {
+            variable: String::from("BILLING_LOG_LEVEL"),
+            path: vec![String::from("log_level")],
+        }
+    );
+}
+
+#[test]
+fn unknown_variables_name_the_prefix() {
+    let error = config()
+        .strict(true)
+        .build_from_iter::<Config, _, _, _>([
+            ("COMMON_DATABASE__URL", "postgres://db/common"),
+            ("COMMON_DATABASE__POOL_SIZE", "8"),
+            ("COMMON_LOG_LEVL", "info"),
+            ("BILLING_LOG_LEVEL", "info"),
+        ])
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        EnvDeserializationError::UnknownVariable {
+            variable: String::from("COMMON_LOG_LEVL"),
+            path: vec![String::from("log_levl")],
+            suggestion: Some(String::from("log_level")),
+        }
+    );
+}
+
+#[test]
+fn with_prefix_replaces_fallback_prefixes() {
+    let error = config()
+        .with_prefix("BILLING_")
+        .build_from_iter::<Config, _, _, _>([
+            ("COMMON_LOG_LEVEL", "info"),
+            ("BILLING_DATABASE__URL", "postgres://db/billing"),
+            ("BILLING_DATABASE__POOL_SIZE", "4"),
+        ])
+        .unwrap_err();
+
+    assert!(
+        matches!(&error, EnvDeserializationError::MissingVariable { .. }),
+        "{:?}",
+        error
+    );
+}
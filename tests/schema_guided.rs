@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use envious::EnvDeserializationError;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Limits {
+    idle: u32,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Database {
+    max: Limits,
+    max_connections: u32,
+    connect_timeout_secs: u64,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Upstream {
+    host_name: String,
+    max_connections: u32,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum Auth {
+    ApiKey { key_id: String },
+    Anonymous,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Config {
+    database: Database,
+    upstreams: HashMap<String, Upstream>,
+    log_level: String,
+    allowed_hosts: Vec<String>,
+    auth: Auth,
+    fallback_auth: Auth,
+}
+
+fn vars() -> Vec<(&'static str, &'static str)> {
+    vec![
+        ("APP_DATABASE_MAX_IDLE", "2"),
+        ("APP_DATABASE_MAX_CONNECTIONS", "16"),
+        ("APP_DATABASE_CONNECT_TIMEOUT_SECS", "5"),
+        ("APP_UPSTREAMS_API_HOST_NAME", "api.internal"),
+        ("APP_UPSTREAMS_API_MAX_CONNECTIONS", "4"),
+        ("APP_LOG_LEVEL", "debug"),
+        ("APP_ALLOWED_HOSTS_0", "localhost"),
+        ("APP_ALLOWED_HOSTS_1", "example.com"),
+        ("APP_AUTH_API_KEY_KEY_ID", "abc"),
+        ("APP_FALLBACK_AUTH", "anonymous"),
+    ]
+}
+
+#[test]
+fn resolve_single_underscore_names() {
+    let config: Config = envious::Config::new()
+        .with_prefix("APP_")
+        .with_separator("_")
+        .schema_guided(true)
+        .build_from_iter(vars())
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            database: Database {
+                max: Limits { idle: 2 },
+                max_connections: 16,
+                connect_timeout_secs: 5,
+            },
+            upstreams: HashMap::from([(
+                String::from("api"),
+                Upstream {
+                    host_name: String::from("api.internal"),
+                    max_connections: 4,
+                }
+            )]),
+            log_level: String::from("debug"),
+            allowed_hosts: vec![String::from("localhost"), String::from("example.com")],
+            auth: Auth::ApiKey {
+                key_id: String::from("abc"),
+            },
+            fallback_auth: Auth::Anonymous,
+        }
+    );
+}
+
+#[test]
+fn missing_fields_are_named_by_variable() {
+    let vars = vars()
+        .into_iter()
+        .filter(|(name, _)| *name != "APP_DATABASE_MAX_CONNECTIONS");
+
+    let error = envious::Config::new()
+        .with_prefix("APP_")
+        .with_separator("_")
+        .schema_guided(true)
+        .build_from_iter::<Config, _, _, _>(vars)
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        EnvDeserializationError::MissingVariable {
+            variable: String::from("APP_DATABASE_MAX_CONNECTIONS"),
+            path: vec![String::from("database"), String::from("max_connections")],
+        }
+    );
+}
+
+#[test]
+fn unknown_variables_are_reported() {
+    let mut vars = vars();
+    vars.push(("APP_DATABASE_MAX_CONECTIONS", "8"));
+
+    let error = envious::Config::new()
+        .with_prefix("APP_")
+        .with_separator("_")
+        .schema_guided(true)
+        .strict(true)
+        .build_from_iter::<Config, _, _, _>(vars)
+        .unwrap_err();
+
+    assert!(
+        matches!(
+            &error,
+            EnvDeserializationError::UnknownVariable { variable, .. }
+                if variable == "APP_DATABASE_MAX_CONECTIONS"
+        ),
+        "{:?}",
+        error
+    );
+}
+
+#[test]
+fn typos_are_not_split_after_shorter_fields() {
+    #[derive(Deserialize, Debug)]
+    struct Database {
+        #[allow(dead_code)]
+        max: u32,
+        #[allow(dead_code)]
+        max_connections: u32,
+    }
+
+    #[derive(Deserialize, Debug)]
+    struct Config {
+        #[allow(dead_code)]
+        database: Database,
+    }
+
+    let error = envious::Config::new()
+        .with_prefix("APP_")
+        .with_separator("_")
+        .schema_guided(true)
+        .strict(true)
+        .build_from_iter::<Config, _, _, _>([
+            ("APP_DATABASE_MAX", "2"),
+            ("APP_DATABASE_MAX_CONECTIONS", "16"),
+        ])
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        EnvDeserializationError::UnknownVariable {
+            variable: String::from("APP_DATABASE_MAX_CONECTIONS"),
+            path: vec![String::from("database"), String::from("max_conections")],
+            suggestion: Some(String::from("max_connections")),
+        }
+    );
+}
+
+#[test]
+fn values_cannot_be_nested_below_simple_values() {
+    #[derive(Deserialize, Debug)]
+    struct Config {
+        #[allow(dead_code)]
+        tls: bool,
+    }
+
+    let error = envious::Config::new()
+        .with_separator("_")
+        .schema_guided(true)
+        .build_from_iter::<Config, _, _, _>([("TLS", "true"), ("TLS_CERT", "cert.pem")])
+        .unwrap_err();
+
+    assert!(
+        matches!(
+            &error,
+            EnvDeserializationError::InVariable { source, .. }
+                if matches!(**source, EnvDeserializationError::InvalidEnvNesting(_))
+        ),
+        "{:?}",
+        error
+    );
+}
+
+#[test]
+fn names_are_split_at_every_separator_by_default() {
+    #[derive(Deserialize, Debug)]
+    struct Config {
+        #[allow(dead_code)]
+        max_connections: u32,
+    }
+
+    let error = envious::Config::new()
+        .with_separator("_")
+        .build_from_iter::<Config, _, _, _>([("MAX_CONNECTIONS", "16")])
+        .unwrap_err();
+
+    assert!(matches!(
+        error,
+        EnvDeserializationError::MissingVariable { .. }
+    ));
+}
@@ -0,0 +1,149 @@
+use envious::EnvDeserializationError;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, PartialEq, Eq, Default)]
+#[serde(default)]
+struct Database {
+    url: String,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+enum Mode {
+    Fast { level: u8 },
+    Slow { level: u8 },
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+struct Config {
+    #[serde(default)]
+    database: Database,
+    mode: Mode,
+}
+
+#[test]
+fn unknown_variables_are_ignored_by_default() {
+    let vars = [
+        ("APP_DATABSE__URL", "postgres://"),
+        ("APP_MODE__FAST__LEVEL", "1"),
+    ];
+
+    let config: Config = envious::Config::new()
+        .with_prefix("APP_")
+        .build_from_iter(vars)
+        .unwrap();
+
+    assert_eq!(config.database, Database::default());
+}
+
+#[test]
+fn unknown_variables_are_rejected_when_strict() {
+    let vars = [
+        ("APP_DATABSE__URL", "postgres://"),
+        ("APP_MODE__FAST__LEVEL", "1"),
+    ];
+
+    let error = envious::Config::new()
+        .with_prefix("APP_")
+        .strict(true)
+        .build_from_iter::<Config, _, _, _>(vars)
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        EnvDeserializationError::UnknownVariable {
+            variable: String::from("APP_DATABSE__URL"),
+            path: vec![String::from("databse"), String::from("url")],
+            suggestion: Some(String::from("database")),
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "Unknown environment variable `APP_DATABSE__URL`, did you mean `database`?"
+    );
+}
+
+#[test]
+fn additional_variants_are_rejected_when_strict() {
+    let vars = [
+        ("APP_MODE__FAST__LEVEL", "1"),
+        ("APP_MODE__SLOW__LEVEL", "2"),
+        ("APP_OTHER", "2"),
+    ];
+
+    let error = envious::Config::new()
+        .with_prefix("APP_")
+        .strict(true)
+        .accumulate_errors(true)
+        .build_from_iter::<Config, _, _, _>(vars)
+        .unwrap_err();
+
+    let EnvDeserializationError::Multiple(errors) = error else {
+        panic!("Expected multiple errors");
+    };
+
+    assert_eq!(errors.len(), 2);
+    assert!(errors.contains(&EnvDeserializationError::UnknownVariable {
+        variable: String::from("APP_OTHER"),
+        path: vec![String::from("other")],
+        suggestion: None,
+    }));
+}
+
+#[test]
+fn unknown_variables_are_reported_when_deserialization_fails() {
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Config {
+        database: Database,
+    }
+
+    let vars = [("APP_DATABSE__URL", "postgres://")];
+
+    let error = envious::Config::new()
+        .with_prefix("APP_")
+        .strict(true)
+        .build_from_iter::<Config, _, _, _>(vars)
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        EnvDeserializationError::UnknownVariable {
+            variable: String::from("APP_DATABSE__URL"),
+            path: vec![String::from("databse"), String::from("url")],
+            suggestion: Some(String::from("database")),
+        }
+    );
+}
+
+#[test]
+fn unknown_fields_inside_maps_are_rejected_when_strict() {
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Config {
+        databases: std::collections::HashMap<String, Database>,
+    }
+
+    let vars = [
+        ("APP_DATABASES__MAIN__URL", "postgres://"),
+        ("APP_DATABASES__BACKUP__URLL", "postgres://"),
+    ];
+
+    let error = envious::Config::new()
+        .with_prefix("APP_")
+        .strict(true)
+        .build_from_iter::<Config, _, _, _>(vars)
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        EnvDeserializationError::UnknownVariable {
+            variable: String::from("APP_DATABASES__BACKUP__URLL"),
+            path: vec![
+                String::from("databases"),
+                String::from("backup"),
+                String::from("urll"),
+            ],
+            suggestion: Some(String::from("url")),
+        }
+    );
+}
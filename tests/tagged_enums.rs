@@ -0,0 +1,161 @@
+use envious::{EnvDeserializationError, InferredType};
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(tag = "kind")]
+enum Storage {
+    Disk { path: String, size: u64 },
+    Memory { limit: i32, compress: bool },
+    Remote { url: String, timeout: f64 },
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(tag = "t", content = "c")]
+enum Cache {
+    Lru(u32),
+    Ttl { seconds: u64, refresh: bool },
+    Disabled,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Config {
+    storage: Storage,
+    cache: Cache,
+}
+
+#[test]
+fn parse_internally_tagged() {
+    let vars = [
+        ("storage__kind", "Memory"),
+        ("storage__limit", "-1"),
+        ("storage__compress", "true"),
+        ("cache__t", "Disabled"),
+    ];
+
+    let config: Config = envious::Config::new()
+        .with_type_inference(InferredType::DEFAULT_PRECEDENCE)
+        .build_from_iter(vars)
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            storage: Storage::Memory {
+                limit: -1,
+                compress: true
+            },
+            cache: Cache::Disabled,
+        }
+    );
+}
+
+#[test]
+fn parse_internally_tagged_with_content() {
+    let vars = [
+        ("storage__kind", "Disk"),
+        ("storage__path", "/var/lib/data"),
+        ("storage__size", "1024"),
+        ("cache__t", "Lru"),
+        ("cache__c", "8"),
+    ];
+
+    let config: Config = envious::Config::new()
+        .with_type_inference(InferredType::DEFAULT_PRECEDENCE)
+        .build_from_iter(vars)
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            storage: Storage::Disk {
+                path: String::from("/var/lib/data"),
+                size: 1024
+            },
+            cache: Cache::Lru(8),
+        }
+    );
+}
+
+#[test]
+fn parse_adjacently_tagged() {
+    let vars = [
+        ("storage__timeout", "2.5"),
+        ("storage__url", "https://example.com"),
+        ("storage__kind", "Remote"),
+        ("cache__c__refresh", "false"),
+        ("cache__c__seconds", "60"),
+        ("cache__t", "Ttl"),
+    ];
+
+    let config: Config = envious::Config::new()
+        .with_type_inference(InferredType::DEFAULT_PRECEDENCE)
+        .build_from_iter(vars)
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            storage: Storage::Remote {
+                url: String::from("https://example.com"),
+                timeout: 2.5
+            },
+            cache: Cache::Ttl {
+                seconds: 60,
+                refresh: false
+            },
+        }
+    );
+}
+
+#[test]
+fn tagged_type_errors_name_variable() {
+    let vars = [
+        ("storage__kind", "Memory"),
+        ("storage__limit", "lots"),
+        ("storage__compress", "true"),
+        ("cache__t", "Disabled"),
+    ];
+
+    let error = envious::Config::new()
+        .with_type_inference(InferredType::DEFAULT_PRECEDENCE)
+        .build_from_iter::<Config, _, _, _>(vars)
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        EnvDeserializationError::InVariable {
+            variable: String::from("storage__limit"),
+            path: vec![String::from("storage"), String::from("limit")],
+            source: Box::new(EnvDeserializationError::GenericDeserialization(
+                String::from("invalid type: string \"lots\", expected i32")
+            )),
+        }
+    );
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(tag = "kind")]
+enum Secret {
+    Password { password: String },
+}
+
+#[test]
+fn tagged_strings_without_inference() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Config {
+        secret: Secret,
+    }
+
+    let vars = [("secret__kind", "Password"), ("secret__password", "1024")];
+
+    let config: Config = envious::Config::new().build_from_iter(vars).unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            secret: Secret::Password {
+                password: String::from("1024"),
+            },
+        }
+    );
+}
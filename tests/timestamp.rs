@@ -0,0 +1,65 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use envious::EnvDeserializationError;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Maintenance {
+    start: SystemTime,
+    end: SystemTime,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Config {
+    cutover: SystemTime,
+    maintenance: Maintenance,
+    deadline: Option<SystemTime>,
+}
+
+#[test]
+fn parse_timestamps() {
+    let vars = [
+        ("CUTOVER", "2024-03-01T12:00:00Z"),
+        ("MAINTENANCE__START", "1709294400"),
+        ("MAINTENANCE__END", "2024-03-01T14:30:00.5+02:00"),
+        ("DEADLINE", "2024-03-01"),
+    ];
+
+    let config: Config = envious::Config::new().build_from_iter(vars).unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            cutover: UNIX_EPOCH + Duration::from_secs(1_709_294_400),
+            maintenance: Maintenance {
+                start: UNIX_EPOCH + Duration::from_secs(1_709_294_400),
+                end: UNIX_EPOCH + Duration::new(1_709_296_200, 500_000_000),
+            },
+            deadline: Some(UNIX_EPOCH + Duration::from_secs(1_709_251_200)),
+        }
+    );
+}
+
+#[test]
+fn invalid_timestamp_names_variable() {
+    let vars = [
+        ("CUTOVER", "2024-03-01T12:00:00Z"),
+        ("MAINTENANCE__START", "2024-03-01T25:00:00Z"),
+        ("MAINTENANCE__END", "2024-03-01"),
+    ];
+
+    let error = envious::Config::new()
+        .build_from_iter::<Config, _, _, _>(vars)
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        EnvDeserializationError::InVariable {
+            variable: String::from("MAINTENANCE__START"),
+            path: vec![String::from("maintenance"), String::from("start")],
+            source: Box::new(EnvDeserializationError::InvalidTimestamp(String::from(
+                "2024-03-01T25:00:00Z"
+            ))),
+        }
+    );
+}
@@ -0,0 +1,95 @@
+use envious::EnvDeserializationError;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Point(f32, f32);
+
+#[derive(Deserialize, Debug, PartialEq)]
+enum Shape {
+    Line(f32, f32),
+    Circle(f32),
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Config {
+    triple: (u8, String, bool),
+    point: Point,
+    shape: Shape,
+    fixed: [u16; 2],
+}
+
+#[test]
+fn parse_tuples() {
+    let vars = [
+        ("triple__2", "true"),
+        ("triple__0", "1"),
+        ("triple__1", "two"),
+        ("point__0", "1.5"),
+        ("point__1", "-2.5"),
+        ("shape__Line__0", "1.0"),
+        ("shape__Line__1", "2.0"),
+        ("fixed__0", "80"),
+        ("fixed__1", "443"),
+    ];
+
+    let config: Config = envious::Config::new().build_from_iter(vars).unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            triple: (1, String::from("two"), true),
+            point: Point(1.5, -2.5),
+            shape: Shape::Line(1.0, 2.0),
+            fixed: [80, 443],
+        }
+    );
+}
+
+#[test]
+fn missing_index_is_named() {
+    #[derive(Deserialize, Debug)]
+    struct Config {
+        #[allow(dead_code)]
+        shape: Shape,
+    }
+
+    let vars = [("SHAPE__LINE__0", "1.0")];
+
+    let error = envious::Config::new()
+        .build_from_iter::<Config, _, _, _>(vars)
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        EnvDeserializationError::MissingVariable {
+            variable: String::from("SHAPE__LINE__1"),
+            path: vec![
+                String::from("shape"),
+                String::from("Line"),
+                String::from("1")
+            ],
+        }
+    );
+}
+
+#[test]
+fn too_many_elements() {
+    let vars = [("point__0", "1"), ("point__1", "2"), ("point__2", "3")];
+
+    #[derive(Deserialize, Debug)]
+    struct Config {
+        #[allow(dead_code)]
+        point: Point,
+    }
+
+    let error = envious::Config::new()
+        .build_from_iter::<Config, _, _, _>(vars)
+        .unwrap_err();
+
+    match error {
+        EnvDeserializationError::InVariable { variable, .. } => {
+            assert_eq!(variable, "point__2")
+        }
+        error => panic!("Unexpected error: {:?}", error),
+    }
+}
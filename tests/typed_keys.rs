@@ -0,0 +1,107 @@
+use std::collections::{BTreeMap, HashMap};
+
+use envious::EnvDeserializationError;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Listener {
+    host: String,
+}
+
+#[derive(Deserialize, Debug, PartialEq, PartialOrd, Eq, Ord)]
+enum Region {
+    EuWest,
+    UsEast,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Config {
+    listeners: HashMap<u16, Listener>,
+    endpoints: BTreeMap<Region, String>,
+    features: HashMap<bool, i8>,
+}
+
+#[test]
+fn parse_typed_keys() {
+    let vars = [
+        ("LISTENERS__8080__HOST", "localhost"),
+        ("LISTENERS__443__HOST", "example.com"),
+        ("ENDPOINTS__EUWEST", "https://eu.example.com"),
+        ("ENDPOINTS__useast", "https://us.example.com"),
+        ("FEATURES__TRUE", "1"),
+        ("FEATURES__false", "-1"),
+    ];
+
+    let config: Config = envious::Config::new().build_from_iter(vars).unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            listeners: HashMap::from([
+                (
+                    8080,
+                    Listener {
+                        host: String::from("localhost")
+                    }
+                ),
+                (
+                    443,
+                    Listener {
+                        host: String::from("example.com")
+                    }
+                ),
+            ]),
+            endpoints: BTreeMap::from([
+                (Region::EuWest, String::from("https://eu.example.com")),
+                (Region::UsEast, String::from("https://us.example.com")),
+            ]),
+            features: HashMap::from([(true, 1), (false, -1)]),
+        }
+    );
+}
+
+#[test]
+fn invalid_key_names_variable() {
+    let vars = [
+        ("LISTENERS__http__HOST", "localhost"),
+        ("ENDPOINTS__EUWEST", "https://eu.example.com"),
+        ("FEATURES__TRUE", "1"),
+    ];
+
+    let error = envious::Config::new()
+        .build_from_iter::<Config, _, _, _>(vars)
+        .unwrap_err();
+
+    match error {
+        EnvDeserializationError::InVariable { variable, path, .. } => {
+            assert_eq!(variable, "LISTENERS__http");
+            assert_eq!(path, ["listeners", "http"]);
+        }
+        error => panic!("Unexpected error: {}", error),
+    }
+}
+
+#[test]
+fn case_sensitive_enum_keys() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Config {
+        endpoints: BTreeMap<Region, String>,
+    }
+
+    let error = envious::Config::new()
+        .case_sensitive(true)
+        .build_from_iter::<Config, _, _, _>([("endpoints__euwest", "https://eu.example.com")])
+        .unwrap_err();
+
+    assert!(matches!(error, EnvDeserializationError::InVariable { .. }));
+
+    let config: Config = envious::Config::new()
+        .case_sensitive(true)
+        .build_from_iter([("endpoints__EuWest", "https://eu.example.com")])
+        .unwrap();
+
+    assert_eq!(
+        config.endpoints,
+        BTreeMap::from([(Region::EuWest, String::from("https://eu.example.com"))])
+    );
+}
@@ -0,0 +1,98 @@
+use envious::EnvDeserializationError;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Cache {
+    size: u64,
+    entries: u32,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Config {
+    cache: Cache,
+    body_limit: usize,
+    offset: i64,
+}
+
+#[test]
+fn parse_unit_suffixes() {
+    let vars = [
+        ("CACHE__SIZE", "512MiB"),
+        ("CACHE__ENTRIES", "10k"),
+        ("BODY_LIMIT", "1.5 MB"),
+        ("OFFSET", "-2Ki"),
+    ];
+
+    let config: Config = envious::Config::new()
+        .unit_suffixes(true)
+        .build_from_iter(vars)
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            cache: Cache {
+                size: 512 * 1024 * 1024,
+                entries: 10_000,
+            },
+            body_limit: 1_500_000,
+            offset: -2048,
+        }
+    );
+}
+
+#[test]
+fn unit_suffixes_per_path() {
+    let vars = [
+        ("CACHE__SIZE", "1GiB"),
+        ("CACHE__ENTRIES", "10k"),
+        ("BODY_LIMIT", "100"),
+        ("OFFSET", "0"),
+    ];
+
+    let config: Config = envious::Config::new()
+        .with_unit_suffixes_for("cache.size")
+        .with_unit_suffixes_for("cache.entries")
+        .build_from_iter(vars)
+        .unwrap();
+
+    assert_eq!(config.cache.size, 1 << 30);
+
+    let error = envious::Config::new()
+        .with_unit_suffixes_for("cache.size")
+        .build_from_iter::<Config, _, _, _>(vars)
+        .unwrap_err();
+
+    assert!(matches!(
+        error,
+        EnvDeserializationError::InVariable { variable, .. } if variable == "CACHE__ENTRIES"
+    ));
+}
+
+#[test]
+fn overflow_names_variable_and_value() {
+    let vars = [
+        ("CACHE__SIZE", "1GiB"),
+        ("CACHE__ENTRIES", "5GiB"),
+        ("BODY_LIMIT", "100"),
+        ("OFFSET", "0"),
+    ];
+
+    let error = envious::Config::new()
+        .unit_suffixes(true)
+        .build_from_iter::<Config, _, _, _>(vars)
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        EnvDeserializationError::InVariable {
+            variable: String::from("CACHE__ENTRIES"),
+            path: vec![String::from("cache"), String::from("entries")],
+            source: Box::new(EnvDeserializationError::GenericDeserialization(
+                String::from(
+                    "'5GiB' could not be deserialized due to: number does not fit into u32"
+                )
+            )),
+        }
+    );
+}
@@ -0,0 +1,197 @@
+use envious::InferredType;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(untagged)]
+enum Port {
+    Number(u16),
+    Named(String),
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(untagged)]
+enum Setting {
+    Enabled(bool),
+    Ratio(f64),
+    Level(i8),
+    Custom { name: String, level: u8 },
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Config {
+    port: Port,
+    setting: Setting,
+}
+
+#[test]
+fn parse_untagged_with_inference() {
+    let vars = [("port", "8080"), ("setting", "true")];
+
+    let config: Config = envious::Config::new()
+        .with_type_inference(InferredType::DEFAULT_PRECEDENCE)
+        .build_from_iter(vars)
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            port: Port::Number(8080),
+            setting: Setting::Enabled(true),
+        }
+    );
+
+    let vars = [
+        ("port", "http"),
+        ("setting__name", "custom"),
+        ("setting__level", "3"),
+    ];
+
+    let config: Config = envious::Config::new()
+        .with_type_inference(InferredType::DEFAULT_PRECEDENCE)
+        .build_from_iter(vars)
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            port: Port::Named(String::from("http")),
+            setting: Setting::Custom {
+                name: String::from("custom"),
+                level: 3
+            },
+        }
+    );
+}
+
+#[test]
+fn inference_precedence() {
+    let vars = [("port", "http"), ("setting", "-3")];
+
+    let config: Config = envious::Config::new()
+        .with_type_inference([InferredType::Float, InferredType::Integer])
+        .build_from_iter(vars)
+        .unwrap();
+
+    assert_eq!(config.setting, Setting::Ratio(-3.0));
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Server {
+        port: Port,
+    }
+
+    let config: Server = envious::Config::new()
+        .with_type_inference([InferredType::String, InferredType::Integer])
+        .build_from_iter([("port", "8080")])
+        .unwrap();
+
+    assert_eq!(config.port, Port::Named(String::from("8080")));
+}
+
+#[test]
+fn untagged_without_inference() {
+    let vars = [("port", "8080"), ("setting", "true")];
+
+    let result = envious::Config::new()
+        .with_type_inference(InferredType::DEFAULT_PRECEDENCE)
+        .without_type_inference()
+        .build_from_iter::<Config, _, _, _>(vars);
+
+    assert!(result.is_err());
+
+    // Nested values are given as strings as well
+    let vars = [
+        ("port", "8080"),
+        ("setting__name", "a"),
+        ("setting__level", "1"),
+    ];
+    let result = envious::Config::new().build_from_iter::<Config, _, _, _>(vars);
+
+    assert!(result.is_err());
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Server {
+        port: Port,
+    }
+
+    let config: Server = envious::Config::new()
+        .build_from_iter([("port", "8080")])
+        .unwrap();
+
+    assert_eq!(config.port, Port::Named(String::from("8080")));
+}
+
+#[test]
+fn untagged_strings_without_inference() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(untagged)]
+    enum Credentials {
+        Password { password: String },
+        Token(String),
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Config {
+        credentials: Credentials,
+        fallback: Credentials,
+    }
+
+    let vars = [("credentials__password", "12345"), ("fallback", "true")];
+
+    let config: Config = envious::Config::new().build_from_iter(vars).unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            credentials: Credentials::Password {
+                password: String::from("12345")
+            },
+            fallback: Credentials::Token(String::from("true")),
+        }
+    );
+}
+
+#[test]
+fn inference_keeps_explicit_types() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Explicit {
+        name: String,
+        count: u8,
+        value: Option<f32>,
+        grade: char,
+    }
+
+    let vars = [
+        ("name", "1234"),
+        ("count", "5"),
+        ("value", "true"),
+        ("grade", "7"),
+    ];
+
+    let result = envious::Config::new()
+        .with_type_inference(InferredType::DEFAULT_PRECEDENCE)
+        .build_from_iter::<Explicit, _, _, _>(vars);
+
+    assert!(result.is_err());
+
+    let vars = [
+        ("name", "1234"),
+        ("count", "5"),
+        ("value", "0.5"),
+        ("grade", "7"),
+    ];
+
+    let config: Explicit = envious::Config::new()
+        .with_type_inference(InferredType::DEFAULT_PRECEDENCE)
+        .build_from_iter(vars)
+        .unwrap();
+
+    assert_eq!(
+        config,
+        Explicit {
+            name: String::from("1234"),
+            count: 5,
+            value: Some(0.5),
+            grade: '7',
+        }
+    );
+}